@@ -137,6 +137,35 @@ fn get_allowed_player_states(attrs: &[Attribute]) -> Option<Vec<Ident>> {
     None
 }
 
+// Extract the name the variant has on the wire, from #[serde(rename = "...")] or the variant name.
+fn get_serialized_name(variant: &syn::Variant) -> String {
+    let mut name = variant.ident.to_string();
+    for attr in &variant.attrs {
+        if attr.path().is_ident("serde") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    name = value.value();
+                }
+                Ok(())
+            });
+        }
+    }
+    name
+}
+
+// MessageTarget and PlayerState are serialized in SCREAMING_SNAKE_CASE, e.g. ToSelf -> TO_SELF.
+fn to_screaming_snake_case(ident: &Ident) -> String {
+    let mut name = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('_');
+        }
+        name.push(c.to_ascii_uppercase());
+    }
+    name
+}
+
 pub fn generate(attr: TokenStream, item: TokenStream) -> TokenStream {
     let args = parse_macro_input!(attr as AutoTriggerArgs);
     let input_ast = parse_macro_input!(item as DeriveInput);
@@ -412,6 +441,68 @@ pub fn generate(attr: TokenStream, item: TokenStream) -> TokenStream {
         })
         .collect();
 
+    let type_name_match_arms: Vec<_> = message_enum_for_match
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = &variant.ident;
+            let variant_name = variant_ident.to_string();
+            let pattern = match &variant.fields {
+                syn::Fields::Unit => quote! { #message_enum_ident::#variant_ident },
+                syn::Fields::Unnamed(_) => quote! { #message_enum_ident::#variant_ident (..) },
+                syn::Fields::Named(_) => quote! { #message_enum_ident::#variant_ident { .. } },
+            };
+            quote! {
+                #pattern => #variant_name
+            }
+        })
+        .collect();
+
+    // Describes the constraints of every message type, used to export the protocol description.
+    let description_items: Vec<_> = message_enum_for_match
+        .variants
+        .iter()
+        .map(|variant| {
+            let message_type = get_serialized_name(variant);
+            let allowed_targets = get_allowed_targets(&variant.attrs)
+                .unwrap_or_default()
+                .iter()
+                .map(to_screaming_snake_case)
+                .collect::<Vec<_>>();
+            let required_player_states = get_allowed_player_states(&variant.attrs)
+                .unwrap_or_default()
+                .iter()
+                .map(to_screaming_snake_case)
+                .collect::<Vec<_>>();
+            let forwarded = get_allowed_behaviour(&variant.attrs)
+                .unwrap_or_default()
+                .first()
+                .is_some_and(|behaviour| behaviour == "Forward");
+            let unique = variant
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("unique"));
+            quote! {
+                MessageTypeDescription {
+                    message_type: #message_type,
+                    allowed_targets: vec![#( #allowed_targets ),*],
+                    required_player_states: vec![#( #required_player_states ),*],
+                    forwarded: #forwarded,
+                    unique: #unique,
+                }
+            }
+        })
+        .collect();
+
+    let description_impl = quote! {
+        impl #message_enum_ident {
+            /// The constraints of every message type, in the order they are declared
+            pub fn message_type_descriptions() -> Vec<MessageTypeDescription> {
+                vec![#( #description_items ),*]
+            }
+        }
+    };
+
     let unique_impl = quote! {
         impl #struct_ident {
             pub fn is_unique(&self) -> bool {
@@ -419,6 +510,13 @@ pub fn generate(attr: TokenStream, item: TokenStream) -> TokenStream {
                     #( #unique_match_arms, )*
                 }
             }
+
+            /// The name of the message type, e.g. "MoveTankCommand"
+            pub fn message_type_name(&self) -> &'static str {
+                match &self.message {
+                    #( #type_name_match_arms, )*
+                }
+            }
         }
     };
 
@@ -429,6 +527,7 @@ pub fn generate(attr: TokenStream, item: TokenStream) -> TokenStream {
         #generated_impl
         #client_generated_impl
         #unique_impl
+        #description_impl
     };
     output.into()
 }
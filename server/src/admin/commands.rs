@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use shared::networking::{
+    lobby_management::{
+        lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
+        LobbyState,
+    },
+    messages::{
+        message_container::{
+            AdminCommandTrigger, MessageContainer, MessageTarget, NetworkMessageType,
+        },
+        message_data::{
+            admin::{AdminCommand, LobbyClientInfo, LobbyInfo, LobbyListData},
+            first_contact::ClientType,
+            message_error_types::ErrorMessageTypes,
+            text_data::TextDataWrapper,
+        },
+        message_queue::ImmediateOutMessageQueue,
+    },
+};
+
+use crate::{
+    gameplay::{
+        end_lobby::EndLobbyTrigger,
+        pause_lobby::{PauseLobbyTrigger, ResumeLobbyTrigger},
+    },
+    networking::handle_clients::lib::{ClientDisconnectedTrigger, MyNetworkClient},
+};
+
+pub fn handle_admin_command(
+    trigger: Trigger<AdminCommandTrigger>,
+    client_types: Query<&ClientType>,
+    network_clients: Query<&MyNetworkClient>,
+    mut lobby_management: LobbyManagementSystemParam,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+    mut commands: Commands,
+) {
+    let sender = trigger.sender.expect("Failed to get sender");
+
+    let answer = if !matches!(client_types.get(sender), Ok(ClientType::Admin)) {
+        warn!(
+            "Client {:?} sent an admin command without being an admin",
+            sender
+        );
+        Err(ErrorMessageTypes::AuthenticationFailed(
+            "Only admin clients can send admin commands".to_string(),
+        ))
+    } else {
+        info!("Admin {:?} sent command {:?}", sender, trigger.message);
+        execute_admin_command(
+            &trigger.message,
+            &network_clients,
+            &mut lobby_management,
+            &mut queues,
+            &mut commands,
+        )
+        .map_err(ErrorMessageTypes::AdminError)
+    };
+
+    let message = match answer {
+        Ok(message) => message,
+        Err(err) => {
+            error!("Failed to execute admin command: {:?}", err);
+            NetworkMessageType::MessageError(err)
+        }
+    };
+
+    if let Ok(mut queue) = queues.get_mut(sender) {
+        queue.push_back(MessageContainer::new(
+            MessageTarget::Client(sender),
+            message,
+        ));
+    }
+}
+
+/// Returns the message the admin gets as an answer
+fn execute_admin_command(
+    command: &AdminCommand,
+    network_clients: &Query<&MyNetworkClient>,
+    lobby_management: &mut LobbyManagementSystemParam,
+    queues: &mut Query<&mut ImmediateOutMessageQueue>,
+    commands: &mut Commands,
+) -> Result<NetworkMessageType, String> {
+    match command {
+        AdminCommand::ListLobbies => Ok(NetworkMessageType::LobbyList(list_lobbies(
+            lobby_management,
+        ))),
+        AdminCommand::PauseLobby { lobby_name } => {
+            let lobby_entity = get_lobby_entity(lobby_management, lobby_name)?;
+            let (_, lobby, _) = lobby_management.get_lobby(lobby_entity)?;
+            if lobby.state != LobbyState::InProgress {
+                return Err(format!("Lobby \"{}\" is not in progress", lobby_name));
+            }
+            commands.trigger_targets(PauseLobbyTrigger, lobby_entity);
+
+            Ok(done(format!("Paused lobby \"{}\"", lobby_name)))
+        }
+        AdminCommand::ResumeLobby { lobby_name } => {
+            let lobby_entity = get_lobby_entity(lobby_management, lobby_name)?;
+            let (_, lobby, _) = lobby_management.get_lobby(lobby_entity)?;
+            if lobby.state != LobbyState::Paused {
+                return Err(format!("Lobby \"{}\" is not paused", lobby_name));
+            }
+            commands.trigger_targets(ResumeLobbyTrigger, lobby_entity);
+
+            Ok(done(format!("Resumed lobby \"{}\"", lobby_name)))
+        }
+        AdminCommand::EndLobby { lobby_name, score } => {
+            let lobby_entity = get_lobby_entity(lobby_management, lobby_name)?;
+            let (_, lobby, mut game_state) = lobby_management.get_lobby_mut(lobby_entity)?;
+            if !matches!(lobby.state, LobbyState::InProgress | LobbyState::Paused) {
+                return Err(format!("Lobby \"{}\" is not running", lobby_name));
+            }
+
+            if let Some(score) = score {
+                game_state.score = score.clone();
+            }
+            commands.trigger_targets(EndLobbyTrigger, lobby_entity);
+
+            Ok(done(format!("Ended lobby \"{}\"", lobby_name)))
+        }
+        AdminCommand::KickClient { client_id } => {
+            let client = network_clients
+                .get(*client_id)
+                .map_err(|_| format!("Client {:?} does not exist", client_id))?;
+            if client.stream.is_none() {
+                return Err(format!(
+                    "Client {:?} is a dummy and can't be kicked",
+                    client_id
+                ));
+            }
+            info!("Kicking client {:?} ({:?})", client_id, client.name);
+            commands.trigger(ClientDisconnectedTrigger(*client_id));
+
+            Ok(done(format!("Kicked client {:?}", client_id)))
+        }
+        AdminCommand::SetTickRate {
+            lobby_name,
+            tick_rate,
+        } => {
+            if *tick_rate == 0 {
+                return Err("The tick rate has to be greater than 0".to_string());
+            }
+
+            let lobby_entity = get_lobby_entity(lobby_management, lobby_name)?;
+            let (_, mut lobby, _) = lobby_management.get_lobby_mut(lobby_entity)?;
+            lobby
+                .tick_timer
+                .set_duration(Duration::from_secs_f32(1.0 / *tick_rate as f32));
+
+            Ok(done(format!(
+                "Lobby \"{}\" now runs with {} ticks per second",
+                lobby_name, tick_rate
+            )))
+        }
+        AdminCommand::Broadcast {
+            lobby_name,
+            message,
+        } => {
+            let lobby_entities = match lobby_name {
+                Some(lobby_name) => vec![get_lobby_entity(lobby_management, lobby_name)?],
+                None => lobby_management
+                    .lobby_resource
+                    .lobbies
+                    .values()
+                    .cloned()
+                    .collect(),
+            };
+
+            for lobby_entity in lobby_entities {
+                let clients = lobby_management.targets_get_players_and_spectators_in_lobby(
+                    LobbyManagementArgument {
+                        lobby: Some(lobby_entity),
+                        ..default()
+                    },
+                )?;
+
+                for client in clients {
+                    if let Ok(mut queue) = queues.get_mut(client) {
+                        queue.push_back(MessageContainer::new(
+                            MessageTarget::Client(client),
+                            NetworkMessageType::SimpleTextMessage(TextDataWrapper::new(
+                                message.clone(),
+                            )),
+                        ));
+                    }
+                }
+            }
+
+            Ok(done("Broadcast sent".to_string()))
+        }
+    }
+}
+
+fn done(message: String) -> NetworkMessageType {
+    NetworkMessageType::AdminCommandDone(TextDataWrapper::new(message))
+}
+
+fn get_lobby_entity(
+    lobby_management: &LobbyManagementSystemParam,
+    lobby_name: &str,
+) -> Result<Entity, String> {
+    lobby_management
+        .lobby_resource
+        .lobbies
+        .get(lobby_name)
+        .cloned()
+        .ok_or(format!("Lobby \"{}\" does not exist", lobby_name))
+}
+
+fn list_lobbies(lobby_management: &LobbyManagementSystemParam) -> LobbyListData {
+    let mut lobbies = lobby_management
+        .lobby_entities
+        .iter()
+        .map(|(_, lobby, game_state)| LobbyInfo {
+            lobby_name: lobby.lobby_name.clone(),
+            map_name: lobby.map_name.clone(),
+            state: lobby.state.clone(),
+            tick: game_state.tick,
+            max_ticks: lobby.max_ticks,
+            tick_rate: 1.0 / lobby.tick_timer.duration().as_secs_f32(),
+            score: game_state.score.clone(),
+            players: lobby
+                .players
+                .iter()
+                .map(|(name, entity, client_type)| LobbyClientInfo {
+                    client_id: *entity,
+                    client_name: name.clone(),
+                    is_dummy: *client_type == ClientType::Dummy,
+                })
+                .collect(),
+            spectators: lobby.spectators.clone(),
+        })
+        .collect::<Vec<_>>();
+    lobbies.sort_by(|a, b| a.lobby_name.cmp(&b.lobby_name));
+
+    LobbyListData { lobbies }
+}
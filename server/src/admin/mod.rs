@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+pub mod commands;
+
+pub struct MyAdminPlugin;
+
+impl Plugin for MyAdminPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(commands::handle_admin_command);
+    }
+}
@@ -26,7 +26,7 @@ pub fn visualize_colliders(
 
 pub fn visualize_world(mut my_gizmos: Gizmos<MyColliderGizmos>, lobbies: Query<&MyLobby>) {
     for lobby in lobbies.iter() {
-        if lobby.state != LobbyState::InProgress {
+        if !matches!(lobby.state, LobbyState::InProgress | LobbyState::Paused) {
             continue;
         }
 
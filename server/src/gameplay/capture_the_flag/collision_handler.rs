@@ -5,7 +5,7 @@ use shared::{
         flag::{FlagBaseMarker, FlagMarker, FlagState},
         player_handling::TankBodyMarker,
     },
-    networking::lobby_management::{InLobby, InTeam},
+    networking::lobby_management::{InLobby, InTeam, MyLobby},
 };
 
 use super::triggers::{
@@ -64,19 +64,24 @@ pub fn handle_collision_with_flag(
 
 pub fn handle_collision_with_flag_base(
     trigger: Trigger<CollidedWithTrigger>,
-    flag_base: Query<(&FlagBaseMarker, &InTeam)>,
+    flag_base: Query<(&FlagBaseMarker, &InTeam, &InLobby)>,
+    lobbies: Query<&MyLobby>,
     flags: Query<(&FlagState, &InTeam, &InLobby), With<FlagMarker>>,
+    tanks: Query<&InTeam, With<TankBodyMarker>>,
     mut commands: Commands,
 ) {
     let my_flag_base_entity = trigger.entity(); // Should always be the flag base
     let collider_entity = trigger.entity; // Should always be the carried flag
 
     // Optimally, the flag base would be my own flag base, and we are carrying the enemy flag.
-    let (my_flag_base_marker, my_flag_base_in_team) = flag_base
+    let (my_flag_base_marker, my_flag_base_in_team, my_flag_base_in_lobby) = flag_base
         .get(my_flag_base_entity)
         .expect("Flag base not found");
 
-    if !my_flag_base_marker.flag_in_base {
+    let own_flag_in_base_to_score = lobbies
+        .get(**my_flag_base_in_lobby)
+        .map_or(true, |lobby| lobby.flag_rules.own_flag_in_base_to_score);
+    if own_flag_in_base_to_score && !my_flag_base_marker.flag_in_base {
         // the flag is not in the base, so we don't care about this collision. As the team can only score when the flag is in the base.
         return;
     }
@@ -102,6 +107,14 @@ pub fn handle_collision_with_flag_base(
 
         match *collided_flag_state {
             FlagState::Carried(carrier_entity) => {
+                // With more than two teams, carriers pass the bases of the other teams without scoring
+                let carrier_owns_base = tanks
+                    .get(carrier_entity)
+                    .is_ok_and(|carrier_in_team| carrier_in_team.0 == my_flag_base_in_team.0);
+                if !carrier_owns_base {
+                    return;
+                }
+
                 commands.trigger_targets(
                     FlagGotDroppedTrigger {
                         carrier: carrier_entity,
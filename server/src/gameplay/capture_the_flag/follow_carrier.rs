@@ -2,23 +2,29 @@ use bevy::prelude::*;
 use shared::{
     game::{
         collision_handling::components::WantedTransform,
-        flag::{FlagMarker, FlagState},
+        flag::{FlagMarker, FlagReturnTimer, FlagState},
         player_handling::TankBodyMarker,
     },
     networking::lobby_management::MyLobby,
 };
 
 use crate::gameplay::{
-    capture_the_flag::triggers::FlagGotDroppedTrigger,
+    capture_the_flag::triggers::{FlagGotDroppedTrigger, ResetFlagTrigger},
     triggers::{MoveFlagsSimulationStepTrigger, UpdateLobbyGameStateTrigger},
 };
 
+/// Moves carried flags with their carrier and counts down the return timers of dropped flags
 pub fn follow_carrier(
     trigger: Trigger<MoveFlagsSimulationStepTrigger>,
     my_lobby: Query<&MyLobby>,
     tanks: Query<&Transform, (With<TankBodyMarker>, Without<FlagMarker>)>,
     mut flags: Query<
-        (&mut Transform, &mut WantedTransform, &FlagState),
+        (
+            &mut Transform,
+            &mut WantedTransform,
+            &FlagState,
+            Option<&mut FlagReturnTimer>,
+        ),
         (With<FlagMarker>, Without<TankBodyMarker>),
     >,
     mut commands: Commands,
@@ -27,7 +33,7 @@ pub fn follow_carrier(
 
     let lobby = my_lobby.get(lobby_entity).expect("Lobby not found");
     for flag in lobby.flags.iter() {
-        let (mut transform, mut wanted_transform, flag_state) =
+        let (mut transform, mut wanted_transform, flag_state, return_timer) =
             flags.get_mut(*flag).expect("Flag not found");
 
         match flag_state {
@@ -51,7 +57,13 @@ pub fn follow_carrier(
                 // Do nothing
             }
             FlagState::Dropped => {
-                // Do nothing
+                // Only set if the lobby returns dropped flags on its own
+                if let Some(mut return_timer) = return_timer {
+                    **return_timer = return_timer.saturating_sub(1);
+                    if **return_timer == 0 {
+                        commands.trigger_targets(ResetFlagTrigger, *flag);
+                    }
+                }
             }
         }
     }
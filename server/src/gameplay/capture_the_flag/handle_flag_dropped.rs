@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use shared::{
     game::{
         collision_handling::components::CollisionLayer,
-        flag::{FlagCarrier, FlagMarker, FlagState},
+        flag::{FlagCarrier, FlagMarker, FlagReturnTimer, FlagState},
     },
     networking::messages::{
         message_container::{MessageContainer, MessageTarget, NetworkMessageType},
@@ -16,7 +16,7 @@ use super::{triggers::FlagGotDroppedTrigger, MyLobby};
 pub fn flag_dropped(
     trigger: Trigger<FlagGotDroppedTrigger>,
     mut flags: Query<(&mut FlagState, &mut CollisionLayer), With<FlagMarker>>,
-    mut lobby_queue: Query<&mut OutMessageQueue, With<MyLobby>>,
+    mut lobby_queue: Query<(&mut OutMessageQueue, &MyLobby)>,
     mut commands: Commands,
 ) {
     let lobby_entity = trigger.entity();
@@ -29,7 +29,7 @@ pub fn flag_dropped(
     flag_collision_layer.ignore.clear(); // Can be picked up by everyone again
     *flag_state = FlagState::Dropped;
 
-    let mut lobby_queue = lobby_queue
+    let (mut lobby_queue, lobby) = lobby_queue
         .get_mut(lobby_entity)
         .expect("Message queue not found");
     lobby_queue.push_back(MessageContainer::new(
@@ -41,4 +41,9 @@ pub fn flag_dropped(
     ));
 
     commands.entity(carrier_entity).remove::<FlagCarrier>();
+    if let Some(return_after_ticks) = lobby.flag_rules.return_after_ticks {
+        commands
+            .entity(flag_entity)
+            .insert(FlagReturnTimer(return_after_ticks));
+    }
 }
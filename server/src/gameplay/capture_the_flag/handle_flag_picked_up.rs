@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use shared::{
     game::{
         collision_handling::components::CollisionLayer,
-        flag::{FlagBaseMarker, FlagCarrier, FlagMarker, FlagState},
+        flag::{FlagBaseMarker, FlagCarrier, FlagMarker, FlagReturnTimer, FlagState},
         player_handling::TankBodyMarker,
     },
     networking::{
@@ -58,4 +58,5 @@ pub fn flag_picked_up(
     commands
         .entity(picker_entity)
         .insert(FlagCarrier { flag: flag_entity });
+    commands.entity(flag_entity).remove::<FlagReturnTimer>();
 }
@@ -2,7 +2,7 @@ use bevy::{ecs::entity::EntityHashSet, prelude::*};
 use shared::{
     game::{
         collision_handling::components::{CollisionLayer, WantedTransform},
-        flag::{FlagBaseMarker, FlagMarker, FlagState},
+        flag::{FlagBaseMarker, FlagMarker, FlagReturnTimer, FlagState},
     },
     networking::{
         lobby_management::{InLobby, InTeam, MyLobby},
@@ -32,6 +32,7 @@ pub fn reset_flag(
         Without<FlagBaseMarker>,
     >,
     mut bases: Query<(&mut FlagBaseMarker, &Transform)>,
+    mut commands: Commands,
 ) {
     let flag_entity = trigger.entity();
 
@@ -68,6 +69,7 @@ pub fn reset_flag(
         *wanted_transform = WantedTransform(*flag_base_transform);
         *transform = *flag_base_transform;
         *flag_state = FlagState::InBase;
+        commands.entity(flag_entity).remove::<FlagReturnTimer>();
 
         *collision_layer =
             CollisionLayer::flag().with_ignore(EntityHashSet::from_iter(team_members.clone()));
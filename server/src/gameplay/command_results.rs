@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+use shared::networking::messages::message_data::command_results::CommandResultsData;
+
+/// Collects the results of the commands a client sent during the current tick.
+/// Gets sent to the client and cleared together with the game state
+#[derive(Debug, Default, Component, Deref, DerefMut)]
+pub struct CommandResults(pub CommandResultsData);
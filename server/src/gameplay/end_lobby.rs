@@ -0,0 +1,102 @@
+use bevy::{prelude::*, utils::HashMap};
+use shared::{
+    game::game_state::LobbyGameState,
+    networking::{
+        lobby_management::{LobbyState, MyLobby},
+        messages::{
+            message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+            message_data::game_finished::GameFinishedData,
+            message_queue::OutMessageQueue,
+        },
+    },
+};
+
+use super::triggers::{AddStateUpdateToQueue, SendOutgoingMessagesTrigger};
+
+/// Ends the game of the targeted lobby
+#[derive(Debug, Event)]
+pub struct EndLobbyTrigger;
+
+/// Triggered globally after a lobby finished its game, containing the final result
+#[derive(Debug, Event)]
+pub struct LobbyFinishedTrigger {
+    pub lobby: Entity,
+    pub result: GameFinishedData,
+}
+
+pub fn check_if_lobby_should_end(
+    trigger: Trigger<AddStateUpdateToQueue>,
+    lobbies: Query<(&MyLobby, &LobbyGameState)>,
+    mut commands: Commands,
+) {
+    let lobby_entity = trigger.entity();
+    let (lobby, game_state) = lobbies.get(lobby_entity).expect("Failed to get lobby");
+
+    if let Some(max_ticks) = lobby.max_ticks {
+        if game_state.tick >= max_ticks {
+            info!(
+                "Lobby \"{}\" reached the maximum of {} ticks, ending the game",
+                lobby.lobby_name, max_ticks
+            );
+            commands.trigger_targets(EndLobbyTrigger, lobby_entity);
+        }
+    }
+}
+
+pub fn end_lobby(
+    trigger: Trigger<EndLobbyTrigger>,
+    mut lobbies: Query<(&mut MyLobby, &LobbyGameState, &mut OutMessageQueue)>,
+    mut commands: Commands,
+) {
+    let lobby_entity = trigger.entity();
+    let (mut lobby, game_state, mut lobby_message_queue) =
+        lobbies.get_mut(lobby_entity).expect("Failed to get lobby");
+
+    if lobby.state == LobbyState::Finished {
+        return;
+    }
+    lobby.state = LobbyState::Finished;
+
+    let mut teams: HashMap<String, Vec<String>> = HashMap::new();
+    if let Some(map_config) = &lobby.map_config {
+        for (team_name, team) in map_config.teams.iter() {
+            let player_names = team
+                .players
+                .iter()
+                .filter_map(|player| {
+                    lobby
+                        .players
+                        .iter()
+                        .find(|(_, entity, _)| entity == player)
+                        .map(|(name, _, _)| name.clone())
+                })
+                .collect();
+            teams.insert(team_name.clone(), player_names);
+        }
+    }
+
+    let result = GameFinishedData {
+        lobby_name: lobby.lobby_name.clone(),
+        map_name: lobby.map_name.clone(),
+        tick: game_state.tick,
+        score: game_state.score.clone(),
+        winner: GameFinishedData::determine_winner(&game_state.score),
+        teams,
+    };
+
+    info!(
+        "Game in lobby \"{}\" finished on tick {} with score {:?}, winner: {:?}",
+        result.lobby_name, result.tick, result.score, result.winner
+    );
+
+    lobby_message_queue.push_back(MessageContainer::new(
+        MessageTarget::AllInLobby,
+        NetworkMessageType::GameFinished(result.clone()),
+    ));
+
+    commands.trigger_targets(SendOutgoingMessagesTrigger, lobby_entity);
+    commands.trigger(LobbyFinishedTrigger {
+        lobby: lobby_entity,
+        result,
+    });
+}
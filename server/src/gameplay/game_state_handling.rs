@@ -4,8 +4,10 @@ use shared::{
         collision_handling::components::Collider,
         flag::{FlagBaseMarker, FlagMarker, FlagState},
         game_state::{
-            ClientState, FlagBaseState, FlagGameState, PersonalizedClientGameState, ProjectileState,
+            ClientState, FlagBaseState, FlagGameState, ObstacleState, PersonalizedClientGameState,
+            ProjectileState,
         },
+        obstacle::ObstacleMarker,
         player_handling::{Health, PlayerState, ShootCooldown, TankBodyMarker, TankTurretMarker},
         projectile_handling::ProjectileMarker,
         tank_types::TankType,
@@ -19,12 +21,16 @@ use shared::{
     },
 };
 
-use crate::gameplay::triggers::UpdateClientGameStatesTrigger;
+use crate::{
+    gameplay::{command_results::CommandResults, triggers::UpdateClientGameStatesTrigger},
+    networking::handle_clients::latency::ConnectionStats,
+};
 
 use super::triggers::{
     AddStateUpdateToQueue, SendOutgoingMessagesTrigger, UpdateLobbyGameStateTrigger,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_lobby_state(
     trigger: Trigger<UpdateLobbyGameStateTrigger>,
     mut lobby_management: LobbyManagementSystemParam,
@@ -37,9 +43,11 @@ pub fn update_lobby_state(
         &PlayerState,
     )>,
     turrets: Query<&Transform, With<TankTurretMarker>>,
+    connection_stats: Query<&ConnectionStats>,
     projectiles: Query<(&Transform, &ProjectileMarker)>,
     flags: Query<(&Transform, &FlagState, &InTeam, &FlagMarker, &Collider)>,
     flag_bases: Query<(&Transform, &FlagBaseMarker, &Collider, &InTeam)>,
+    obstacles: Query<(&Transform, &ObstacleMarker, &Collider, &Health)>,
     mut commands: Commands,
 ) {
     let lobby_entity = trigger.entity();
@@ -76,6 +84,14 @@ pub fn update_lobby_state(
         .iter()
         .map(|entity| *entity)
         .collect::<HashSet<_>>();
+    let obstacle_entities = lobby_management
+        .get_lobby(lobby_entity)
+        .expect("Failed to get lobby")
+        .1
+        .obstacles
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>();
     let mut lobby_game_state = lobby_management
         .get_lobby_gamestate_mut(lobby_entity)
         .expect("Failed to get lobby game state");
@@ -98,6 +114,10 @@ pub fn update_lobby_state(
         client_state.shoot_cooldown = shoot_cooldown.ticks_left;
         client_state.current_health = tank_health.health;
         client_state.state = Some(player_state.clone());
+        if let Ok(stats) = connection_stats.get(*player_entity) {
+            client_state.rtt_ms = stats.rtt_ms;
+            client_state.tick_offset = stats.tick_offset;
+        }
     }
 
     // Updating states of all projectiles and removing those that are not in the world anymore from the game state
@@ -173,6 +193,30 @@ pub fn update_lobby_state(
             });
     }
 
+    // Updating states of all obstacles and removing the destroyed ones from the game state
+    lobby_game_state
+        .obstacles
+        .retain(|entity, _| obstacle_entities.contains(entity));
+    for obstacle_entity in obstacle_entities.iter() {
+        let (obstacle_transform, obstacle_marker, obstacle_collider, obstacle_health) = obstacles
+            .get(*obstacle_entity)
+            .expect("Failed to get obstacle");
+
+        lobby_game_state
+            .obstacles
+            .entry(*obstacle_entity)
+            .and_modify(|state| state.current_health = obstacle_health.health)
+            .or_insert_with(|| ObstacleState {
+                obstacle_id: *obstacle_entity,
+                obstacle_type: obstacle_marker.obstacle_type,
+                tile: obstacle_marker.tile.clone(),
+                transform: *obstacle_transform,
+                collider_size: obstacle_collider.half_size * 2.0,
+                current_health: obstacle_health.health,
+                max_health: obstacle_health.max_health,
+            });
+    }
+
     commands.trigger_targets(UpdateClientGameStatesTrigger, player_entities);
 }
 
@@ -184,7 +228,8 @@ pub fn check_if_client_states_are_all_up_to_date(
     // Go through all lobbies, get their game state, then check for all clients if they have the same tick
     for (entity, mut lobby, game_state) in lobby_management.lobby_entities.iter_mut() {
         match lobby.state {
-            LobbyState::InProgress => (),
+            // A paused lobby still sends out the state of the last tick it processed
+            LobbyState::InProgress | LobbyState::Paused => (),
             _ => continue,
         }
 
@@ -214,6 +259,7 @@ pub fn add_current_game_state_to_message_queue(
     lobby_management: LobbyManagementSystemParam,
     mut out_message_queues: Query<&mut OutMessageQueue>,
     client_states: Query<&PersonalizedClientGameState>,
+    mut command_results: Query<&mut CommandResults>,
     mut commands: Commands,
 ) {
     let lobby_entity = trigger.entity();
@@ -240,6 +286,14 @@ pub fn add_current_game_state_to_message_queue(
 
         // Make sure the game state is sent before any other messages
         out_message_queue.push_front(message);
+
+        if let Ok(mut command_results) = command_results.get_mut(*player_entity) {
+            command_results.tick = lobby_state.tick;
+            out_message_queue.push_back(MessageContainer::new(
+                MessageTarget::Client(*player_entity),
+                NetworkMessageType::CommandResults(std::mem::take(&mut command_results.0)),
+            ));
+        }
     }
 
     // Sending the (global) game state to all spectators
@@ -1,9 +1,13 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashSet};
 use shared::{
-    game::collision_handling::{
-        components::{Collider, CollisionLayer, WantedTransform},
-        structs::Obb3d,
-        triggers::{CollidedWithTrigger, CollidedWithWorldTrigger},
+    game::{
+        collision_handling::{
+            components::{Collider, CollisionLayer, WantedTransform},
+            structs::Obb3d,
+            triggers::{CollidedWithTrigger, CollidedWithWorldTrigger},
+        },
+        obstacle::ObstacleMarker,
+        projectile_handling::ProjectileMarker,
     },
     networking::lobby_management::{InLobby, MyLobby},
 };
@@ -12,18 +16,23 @@ use crate::gameplay::triggers::{CheckForCollisionsTrigger, DespawnOutOfBoundsPro
 
 const STEP_SIZE: f32 = 0.05;
 
+type CollidingEntity<'a> = (
+    Entity,
+    &'a Transform,
+    &'a mut WantedTransform,
+    &'a Collider,
+    &'a CollisionLayer,
+    &'a InLobby,
+    Has<ProjectileMarker>,
+    Has<ObstacleMarker>,
+);
+
 pub fn unified_collision_system(
     trigger: Trigger<CheckForCollisionsTrigger>,
     lobby: Query<&MyLobby>,
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &Transform,
-        &mut WantedTransform,
-        &Collider,
-        &CollisionLayer,
-        &InLobby,
-    )>,
+    mut query: Query<CollidingEntity>,
+    obstacles: Query<&ObstacleMarker>,
     #[cfg(feature = "debug")] mut debug_obb_gizmos: Query<(
         &mut debug::DebugColliderComponent,
         &InLobby,
@@ -39,6 +48,14 @@ pub fn unified_collision_system(
         .as_ref()
         .expect("Map config is missing, you miserable wretch!")
         .map;
+    let layer_grid = &my_lobby.layer_grid;
+    // Tanks can't drive onto the tiles of obstacles that are still standing
+    let obstacle_tiles = my_lobby
+        .obstacles
+        .iter()
+        .filter_map(|obstacle| obstacles.get(*obstacle).ok())
+        .map(|obstacle| (obstacle.tile.x, obstacle.tile.y))
+        .collect::<HashSet<_>>();
 
     #[cfg(feature = "debug")]
     {
@@ -65,10 +82,24 @@ pub fn unified_collision_system(
         entity_collision_time: f32,
         // New: true if this entity's collision layer is NO_COLLISION.
         no_collision: bool,
+        // Projectiles fly over layers that only block movement, e.g. water
+        is_projectile: bool,
+        // Obstacles stand on their own tile, so it must not block them
+        is_obstacle: bool,
     }
 
     let mut sim_entities = Vec::new();
-    for (entity, transform, wanted, collider, collision_layer, in_lobby) in query.iter_mut() {
+    for (
+        entity,
+        transform,
+        wanted,
+        collider,
+        collision_layer,
+        in_lobby,
+        is_projectile,
+        is_obstacle,
+    ) in query.iter_mut()
+    {
         if in_lobby.0 != my_lobby_entity {
             continue;
         }
@@ -83,6 +114,8 @@ pub fn unified_collision_system(
             world_safe: *transform,
             entity_collision_time: 1.0,
             no_collision: collision_layer.contains(CollisionLayer::NO_COLLISION),
+            is_projectile,
+            is_obstacle,
         });
     }
 
@@ -150,6 +183,21 @@ pub fn unified_collision_system(
                         local_collision = true;
                         break 'tile_loop;
                     }
+                    let tile = (tx as usize, tz as usize);
+                    // Projectiles fly over walls, if they are high enough
+                    let blocked = if sim.is_projectile {
+                        layer_grid.tile_blocks_at_height(
+                            tile,
+                            candidate.translation.y - sim.collider.half_size.y,
+                        )
+                    } else {
+                        layer_grid.tile_blocks_movement(tile)
+                            || (!sim.is_obstacle && obstacle_tiles.contains(&tile))
+                    };
+                    if blocked {
+                        local_collision = true;
+                        break 'tile_loop;
+                    }
                     match map_def.get_floor_height_of_tile((tx as usize, tz as usize)) {
                         Some(height) => tile_heights.push(height),
                         None => {
@@ -165,6 +213,14 @@ pub fn unified_collision_system(
             }
             let candidate_floor = tile_heights.clone().into_iter().fold(f32::MIN, f32::max);
             if sim.collider.max_slope == 0.0 {
+                // Projectiles follow the ground between the tile centers, so shots can skim along a slope
+                let candidate_floor = if sim.is_projectile {
+                    map_def
+                        .sample_height(candidate.translation)
+                        .unwrap_or(candidate_floor)
+                } else {
+                    candidate_floor
+                };
                 if candidate.translation.y < candidate_floor + sim.collider.half_size.y {
                     sim.world_collision_time = Some(t);
                     break;
@@ -181,14 +237,21 @@ pub fn unified_collision_system(
                     break;
                 }
 
-                // Otherwise, climb: update y to the maximum floor height plus half the collider’s height.
+                // Otherwise, climb: stand on the interpolated ground and tilt with it.
+                let ground_height = map_def
+                    .sample_height(candidate.translation)
+                    .unwrap_or(candidate_max_floor);
                 safe_transform = Transform {
                     translation: Vec3::new(
                         candidate.translation.x,
-                        candidate_max_floor,
+                        ground_height,
                         candidate.translation.z,
                     ),
-                    rotation: candidate.rotation,
+                    rotation: map_def.get_ground_rotation(
+                        candidate.translation,
+                        candidate.rotation,
+                        sim.collider.half_size,
+                    ),
                     scale: candidate.scale,
                 };
             }
@@ -281,7 +344,7 @@ pub fn unified_collision_system(
             sim.world_safe
         } else {
             // No collision: full movement is safe.
-            // If climbing is allowed, update the y component from safe transform.
+            // If climbing is allowed, update the y component and the tilt from safe transform.
             if sim.collider.max_slope > 0.0 {
                 Transform {
                     translation: Vec3::new(
@@ -289,7 +352,7 @@ pub fn unified_collision_system(
                         sim.world_safe.translation.y,
                         sim.wanted.translation.z,
                     ),
-                    rotation: sim.wanted.rotation,
+                    rotation: sim.world_safe.rotation,
                     scale: sim.wanted.scale,
                 }
             } else {
@@ -0,0 +1,54 @@
+use bevy::prelude::*;
+use shared::{
+    game::{
+        collision_handling::components::CollisionLayer, obstacle::ObstacleMarker,
+        player_handling::Health,
+    },
+    networking::{
+        lobby_management::MyLobby,
+        messages::{
+            message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+            message_data::obstacle_destroyed::ObstacleDestroyedData,
+            message_queue::OutMessageQueue,
+        },
+    },
+};
+
+use crate::networking::handle_clients::lib::MyNetworkClient;
+
+use super::{lobby_cleanup::CleanupNextTick, triggers::CheckHealthTrigger};
+
+/// Removes obstacles without health left, which frees their tile for tanks
+pub fn destroy_obstacles(
+    trigger: Trigger<CheckHealthTrigger>,
+    mut lobby: Query<(&mut MyLobby, &mut OutMessageQueue), Without<MyNetworkClient>>,
+    mut obstacles: Query<(&ObstacleMarker, &Health, &mut CollisionLayer)>,
+    mut commands: Commands,
+) {
+    let lobby_entity = trigger.entity();
+    let (mut lobby, mut lobby_message_queue) =
+        lobby.get_mut(lobby_entity).expect("Lobby not found");
+
+    lobby.obstacles.retain(|obstacle_entity| {
+        let Ok((obstacle, health, mut collision_layer)) = obstacles.get_mut(*obstacle_entity)
+        else {
+            return false;
+        };
+        if health.health > 0.0 {
+            return true;
+        }
+
+        // Projectiles fly through it until it is despawned on the next tick
+        *collision_layer = CollisionLayer::none();
+        commands.entity(*obstacle_entity).insert(CleanupNextTick);
+        lobby_message_queue.push_back(MessageContainer::new(
+            MessageTarget::AllInLobby,
+            NetworkMessageType::ObstacleDestroyed(ObstacleDestroyedData {
+                obstacle_id: *obstacle_entity,
+                obstacle_type: obstacle.obstacle_type,
+                tile: obstacle.tile.clone(),
+            }),
+        ));
+        false
+    });
+}
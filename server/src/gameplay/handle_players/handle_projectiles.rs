@@ -8,6 +8,7 @@ use shared::{
             triggers::{CollidedWithTrigger, CollidedWithWorldTrigger},
         },
         common_components::{Gravity, TickBasedDespawnTimer, Velocity},
+        obstacle::ObstacleMarker,
         player_handling::{Health, PlayerState, TankBodyMarker},
         projectile_handling::ProjectileMarker,
         tank_types::TankType,
@@ -45,6 +46,10 @@ pub fn colliding_with_entity(
         ),
         With<TankBodyMarker>,
     >,
+    mut obstacles: Query<
+        (&Transform, &Collider, &mut Health),
+        (With<ObstacleMarker>, Without<TankBodyMarker>),
+    >,
     mut commands: Commands,
 ) {
     let projectile_entity = trigger.entity();
@@ -53,51 +58,22 @@ pub fn colliding_with_entity(
         .expect("Failed to get projectile");
     let collided_with = trigger.event().entity;
 
-    let mut hit_a_tank = false;
+    let mut hit_something = false;
     let mut hit_side = Side::default();
     let mut damage_dealt = 0.0;
     if let Ok((body_transform, body_collider, tank_type, state, mut health, mut message_queue)) =
         players.get_mut(collided_with)
     {
         if state == &PlayerState::Alive {
-            hit_a_tank = true;
+            hit_something = true;
             let tank_config = tank_configs
                 .get_tank_type_config(tank_type)
                 .expect("Failed to get tank config");
-            let body_half_size = body_collider.half_size;
-
-            // Get the relative vector from the body to the projectile in world space.
-            let relative = projectile_transform.translation - body_transform.translation;
-
-            // Transform the relative vector into the body's local space.
-            let local_pos = body_transform.rotation.inverse() * relative;
-
-            let face_dx = body_half_size.x - local_pos.x.abs();
-            let face_dy = body_half_size.y - local_pos.y.abs();
-            let face_dz = body_half_size.z - local_pos.z.abs();
-
-            hit_side = if face_dx < face_dy && face_dx < face_dz {
-                // Collision on x-axis (left or right)
-                if local_pos.x > 0.0 {
-                    Side::Left
-                } else {
-                    Side::Right
-                }
-            } else if face_dy < face_dx && face_dy < face_dz {
-                // Collision on y-axis (top or bottom)
-                if local_pos.y > 0.0 {
-                    Side::Top
-                } else {
-                    Side::Bottom
-                }
-            } else {
-                // Collision on z-axis (front or back)
-                if local_pos.z > 0.0 {
-                    Side::Front
-                } else {
-                    Side::Back
-                }
-            };
+            hit_side = get_hit_side(
+                body_transform,
+                body_collider.half_size,
+                projectile_transform.translation,
+            );
 
             let armor = tank_config
                 .armor
@@ -118,7 +94,23 @@ pub fn colliding_with_entity(
         }
     }
 
-    if hit_a_tank {
+    if let Ok((obstacle_transform, obstacle_collider, mut health)) =
+        obstacles.get_mut(collided_with)
+    {
+        // Another projectile may have destroyed it earlier in this tick
+        if health.health > 0.0 {
+            hit_something = true;
+            hit_side = get_hit_side(
+                obstacle_transform,
+                obstacle_collider.half_size,
+                projectile_transform.translation,
+            );
+            damage_dealt = projectile.damage;
+            health.health -= projectile.damage;
+        }
+    }
+
+    if hit_something {
         if let Ok((_, _, _, _, _, mut projectile_owner_message_queue)) =
             players.get_mut(projectile.owner)
         {
@@ -137,6 +129,42 @@ pub fn colliding_with_entity(
     commands.entity(projectile_entity).insert(CleanupNextTick);
 }
 
+/// The side of a collider that a projectile at `projectile_position` hit
+fn get_hit_side(transform: &Transform, half_size: Vec3, projectile_position: Vec3) -> Side {
+    // Get the relative vector from the body to the projectile in world space.
+    let relative = projectile_position - transform.translation;
+
+    // Transform the relative vector into the body's local space.
+    let local_pos = transform.rotation.inverse() * relative;
+
+    let face_dx = half_size.x - local_pos.x.abs();
+    let face_dy = half_size.y - local_pos.y.abs();
+    let face_dz = half_size.z - local_pos.z.abs();
+
+    if face_dx < face_dy && face_dx < face_dz {
+        // Collision on x-axis (left or right)
+        if local_pos.x > 0.0 {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    } else if face_dy < face_dx && face_dy < face_dz {
+        // Collision on y-axis (top or bottom)
+        if local_pos.y > 0.0 {
+            Side::Top
+        } else {
+            Side::Bottom
+        }
+    } else {
+        // Collision on z-axis (front or back)
+        if local_pos.z > 0.0 {
+            Side::Front
+        } else {
+            Side::Back
+        }
+    }
+}
+
 pub fn handle_despawn_timer(
     trigger: Trigger<StartNextTickProcessingTrigger>,
     mut lobby: Query<&mut MyLobby>,
@@ -10,22 +10,32 @@ use shared::{
     },
     networking::{
         lobby_management::{InLobby, MyLobby},
-        messages::message_container::ShootCommandTrigger,
+        messages::{
+            message_container::{NetworkMessageType, ShootCommandTrigger},
+            message_data::command_results::{CommandRejectionReason, ProcessedCommand},
+        },
     },
 };
 
-use crate::gameplay::triggers::StartNextTickProcessingTrigger;
+use crate::gameplay::{command_results::CommandResults, triggers::StartNextTickProcessingTrigger};
 
 pub fn handle_tank_shooting_command(
     trigger: Trigger<ShootCommandTrigger>,
     mut lobby: Query<&mut MyLobby>,
-    mut body: Query<(&TankType, &mut ShootCooldown, &TankBodyMarker, &InLobby)>,
-    turret_transform: Query<&GlobalTransform, With<TankTurretMarker>>,
+    mut body: Query<(
+        &TankType,
+        &mut ShootCooldown,
+        &TankBodyMarker,
+        &InLobby,
+        &mut CommandResults,
+        &Transform,
+    )>,
+    turret_transform: Query<&Transform, With<TankTurretMarker>>,
     tank_config: TankConfigSystemParam,
     mut commands: Commands,
 ) {
     let client_entity = trigger.entity();
-    let (tank_type, mut cooldown, tank_body, in_lobby) = body
+    let (tank_type, mut cooldown, tank_body, in_lobby, mut command_results, body_transform) = body
         .get_mut(client_entity)
         .expect("Failed to get tank transform");
 
@@ -40,6 +50,10 @@ pub fn handle_tank_shooting_command(
         let turret_transform = turret_transform
             .get(turret_entity)
             .expect("Failed to get turret transform");
+        // Built from the body instead of the propagated GlobalTransform, so the tilt of the body on a slope
+        // is already up to date
+        let turret_transform =
+            GlobalTransform::from(*body_transform).mul_transform(*turret_transform);
 
         let bullet_spawn_position = turret_transform.translation();
         let bullet_spawn_rotation = turret_transform.rotation();
@@ -59,6 +73,7 @@ pub fn handle_tank_shooting_command(
                     just_spawned: true,
                 },
                 CollisionLayer::player()
+                    .with_additional_layers(&[CollisionLayer::OBSTACLE])
                     .with_ignore(EntityHashSet::from_iter(vec![client_entity, turret_entity])),
                 in_lobby.clone(),
                 Velocity::default(),
@@ -72,6 +87,15 @@ pub fn handle_tank_shooting_command(
         lobby.projectiles.push(bullet);
 
         cooldown.ticks_left = cooldown.ticks_cooldown;
+        command_results
+            .processed
+            .push(ProcessedCommand::ShootCommand);
+    } else {
+        command_results.reject(
+            NetworkMessageType::ShootCommand(trigger.message.clone()),
+            CommandRejectionReason::OnCooldown,
+            format!("Can shoot again in {} ticks", cooldown.ticks_left),
+        );
     }
 }
 
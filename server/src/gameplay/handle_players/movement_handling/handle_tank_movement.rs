@@ -1,27 +1,59 @@
 use bevy::prelude::*;
 use shared::{
     asset_handling::config::TankConfigSystemParam,
-    game::{collision_handling::components::WantedTransform, tank_types::TankType},
-    networking::messages::message_container::MoveTankCommandTrigger,
+    game::{
+        collision_handling::components::WantedTransform, flag::FlagCarrier, tank_types::TankType,
+    },
+    networking::{
+        lobby_management::{InLobby, MyLobby},
+        messages::{
+            message_container::MoveTankCommandTrigger,
+            message_data::command_results::ProcessedCommand,
+        },
+    },
 };
 
+use crate::gameplay::command_results::CommandResults;
+
 pub fn handle_tank_movement(
     trigger: Trigger<MoveTankCommandTrigger>,
-    mut tank: Query<(&mut WantedTransform, &TankType)>,
+    mut tank: Query<(
+        &mut WantedTransform,
+        &TankType,
+        &mut CommandResults,
+        &InLobby,
+        Option<&FlagCarrier>,
+    )>,
+    lobbies: Query<&MyLobby>,
     tank_config: TankConfigSystemParam,
 ) {
     let client_entity = trigger.entity();
-    let (mut tank_transform, tank_type) = tank
+    let (mut tank_transform, tank_type, mut command_results, in_lobby, flag_carrier) = tank
         .get_mut(client_entity)
         .expect("Failed to get tank transform");
     let tank_config = tank_config
         .get_tank_type_config(tank_type)
         .expect("Failed to get tank config");
-    let distance = trigger
-        .distance
-        .clamp(-tank_config.move_speed, tank_config.move_speed);
+    let lobby = lobbies.get(**in_lobby).ok();
+    // Layers of the tile the tank is on change its speed, e.g. mud or roads
+    let layer_speed_modifier = lobby
+        .and_then(|lobby| lobby.layer_grid.get_below(tank_transform.translation))
+        .map_or(1.0, |tile_layers| tile_layers.speed_modifier);
+    let carrier_speed_modifier = lobby
+        .filter(|_| flag_carrier.is_some())
+        .map_or(1.0, |lobby| lobby.flag_rules.carrier_speed_modifier);
+    let speed_modifier = layer_speed_modifier * carrier_speed_modifier;
+    let move_speed = tank_config.move_speed * speed_modifier;
+    let distance = trigger.distance.clamp(-move_speed, move_speed);
     let move_direction = tank_transform.rotation * Vec3::new(0.0, 0.0, distance);
     let next_tank_position = tank_transform.translation + move_direction;
 
     tank_transform.translation = next_tank_position;
+
+    command_results
+        .processed
+        .push(ProcessedCommand::MoveTankCommand {
+            requested_distance: trigger.distance,
+            effective_distance: distance,
+        });
 }
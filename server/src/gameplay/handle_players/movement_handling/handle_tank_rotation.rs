@@ -1,17 +1,28 @@
 use bevy::prelude::*;
 use shared::{
     asset_handling::config::TankConfigSystemParam,
-    game::{collision_handling::components::WantedTransform, player_handling::TankBodyMarker, tank_types::TankType},
-    networking::messages::message_container::RotateTankBodyCommandTrigger,
+    game::{
+        collision_handling::components::WantedTransform, player_handling::TankBodyMarker,
+        tank_types::TankType,
+    },
+    networking::messages::{
+        message_container::RotateTankBodyCommandTrigger,
+        message_data::command_results::ProcessedCommand,
+    },
 };
 
+use crate::gameplay::command_results::CommandResults;
+
 pub fn handle_tank_body_rotation(
     trigger: Trigger<RotateTankBodyCommandTrigger>,
-    mut body_transform: Query<(&mut WantedTransform, &TankType), With<TankBodyMarker>>,
+    mut body_transform: Query<
+        (&mut WantedTransform, &TankType, &mut CommandResults),
+        With<TankBodyMarker>,
+    >,
     tank_config: TankConfigSystemParam,
 ) {
     let client_entity = trigger.entity();
-    let (mut tank_transform, tank_type) = body_transform
+    let (mut tank_transform, tank_type, mut command_results) = body_transform
         .get_mut(client_entity)
         .expect("Failed to get tank transform");
     let tank_config = tank_config
@@ -24,4 +35,11 @@ pub fn handle_tank_body_rotation(
     );
 
     tank_transform.rotation *= Quat::from_rotation_y(rotation);
+
+    command_results
+        .processed
+        .push(ProcessedCommand::RotateTankBodyCommand {
+            requested_angle: trigger.angle,
+            effective_angle: rotation,
+        });
 }
@@ -2,35 +2,55 @@ use bevy::prelude::*;
 use shared::{
     asset_handling::config::TankConfigSystemParam,
     game::{
+        flag::FlagCarrier,
         player_handling::{TankBodyMarker, TankTurretMarker},
         tank_types::TankType,
     },
-    networking::messages::message_container::RotateTankTurretCommandTrigger,
+    networking::{
+        lobby_management::{InLobby, MyLobby},
+        messages::{
+            message_container::{NetworkMessageType, RotateTankTurretCommandTrigger},
+            message_data::command_results::{CommandRejectionReason, ProcessedCommand},
+        },
+    },
 };
 
+use crate::gameplay::command_results::CommandResults;
+
 pub fn handle_tank_turret_rotation(
     trigger: Trigger<RotateTankTurretCommandTrigger>,
-    body: Query<(&TankType, &TankBodyMarker), Without<TankTurretMarker>>,
+    body: Query<
+        (&TankType, &TankBodyMarker, &InLobby, Option<&FlagCarrier>),
+        Without<TankTurretMarker>,
+    >,
     mut turret_transform: Query<&mut Transform, With<TankTurretMarker>>,
+    mut command_results: Query<&mut CommandResults>,
+    lobbies: Query<&MyLobby>,
     tank_config: TankConfigSystemParam,
 ) {
     let client_entity = trigger.entity();
-    let (tank_type, tank_body) = body
+    let (tank_type, tank_body, in_lobby, flag_carrier) = body
         .get(client_entity)
         .expect("Failed to get tank transform");
     let tank_config = tank_config
         .get_tank_type_config(tank_type)
         .expect("Failed to get tank config");
+    let mut command_results = command_results
+        .get_mut(client_entity)
+        .expect("Failed to get command results");
+
+    // Flag carriers might turn their turret slower, depending on the flag rules of the lobby
+    let speed_modifier = lobbies
+        .get(**in_lobby)
+        .ok()
+        .filter(|_| flag_carrier.is_some())
+        .map_or(1.0, |lobby| lobby.flag_rules.carrier_turret_speed_modifier);
+    let yaw_speed = tank_config.turret_yaw_rotation_speed * speed_modifier;
+    let pitch_speed = tank_config.turret_pitch_rotation_speed * speed_modifier;
 
     // Calculate the delta rotations for yaw and pitch.
-    let yaw_delta = trigger.yaw_angle.clamp(
-        -tank_config.turret_yaw_rotation_speed,
-        tank_config.turret_yaw_rotation_speed,
-    );
-    let pitch_delta = trigger.pitch_angle.clamp(
-        -tank_config.turret_pitch_rotation_speed,
-        tank_config.turret_pitch_rotation_speed,
-    );
+    let yaw_delta = trigger.yaw_angle.clamp(-yaw_speed, yaw_speed);
+    let pitch_delta = trigger.pitch_angle.clamp(-pitch_speed, pitch_speed);
 
     // Retrieve the turret entity and its transform.
     let turret_entity = tank_body.turret.expect("Failed to get turret entity");
@@ -56,10 +76,24 @@ pub fn handle_tank_turret_rotation(
             "Error: max_pitch ({}) is greater than min_pitch ({}). Ignoring rotation.",
             max_pitch, min_pitch
         );
+        command_results.reject(
+            NetworkMessageType::RotateTankTurretCommand(trigger.message.clone()),
+            CommandRejectionReason::Invalid,
+            "The pitch limits of the tank are misconfigured on the server".to_string(),
+        );
         return;
     }
     let new_pitch = new_pitch.clamp(max_pitch, min_pitch);
 
     // Construct a new rotation with roll forcibly set to zero.
     turret_transform.rotation = Quat::from_euler(EulerRot::YXZ, new_yaw, new_pitch, 0.0);
+
+    command_results
+        .processed
+        .push(ProcessedCommand::RotateTankTurretCommand {
+            requested_yaw_angle: trigger.yaw_angle,
+            requested_pitch_angle: trigger.pitch_angle,
+            effective_yaw_angle: yaw_delta,
+            effective_pitch_angle: new_pitch - current_pitch,
+        });
 }
@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 use shared::{
+    asset_handling::layer_grid::LayerGrid,
     game::game_state::PersonalizedClientGameState,
     networking::lobby_management::{lobby_management::LobbyManagementSystemParam, InLobby, InTeam},
 };
 
 use crate::gameplay::triggers::UpdateClientGameStatesTrigger;
 
+/// Enemies in a layer that hides tanks (e.g. forest) are only visible to teams that are this close
+const SPOT_DISTANCE_IN_COVER: f32 = 3.0;
+
 /// Based on game world, lobby state, and other things, update the personalized state of each client
 /// So that they only know what they should know
 pub fn update_client_states(
@@ -64,6 +68,8 @@ pub fn update_client_states(
     lobby_state.flag_bases.iter().for_each(|(entity, state)| {
         client_state.flag_bases.insert(*entity, state.clone());
     });
+    // Obstacles are seen by everyone, replacing them drops the destroyed ones
+    client_state.obstacles = lobby_state.obstacles.clone();
 
     // Copying the states of our teammates from the lobby state to the client state
     team_players.iter().for_each(|entity| {
@@ -104,17 +110,33 @@ pub fn update_client_states(
             });
     });
 
+    // Where this client and its teammates are, to find out which hidden enemies they can spot
+    let team_positions = lobby_state
+        .client_states
+        .iter()
+        .filter(|(entity, _)| **entity == client_entity || team_players.contains(entity))
+        .filter_map(|(_, state)| state.transform_body.map(|transform| transform.translation))
+        .collect::<Vec<_>>();
+    let layer_grid = &my_lobby.layer_grid;
+
     // TODO: Only partially copy the states of the enemies from the lobby state to the client state
-    // For now, we simply copy all states from the lobby state to the client state
+    // For now, we copy all states from the lobby state to the client state, except for enemies hidden in cover
     other_players.iter().for_each(|entity| {
         lobby_state
             .client_states
             .iter()
             .for_each(|(state_entity, state)| {
                 if state_entity == entity {
+                    let mut state = state.clone();
+                    let hidden = state.transform_body.is_some_and(|transform| {
+                        is_hidden(layer_grid, transform.translation, &team_positions)
+                    });
+                    if hidden {
+                        state.clear_non_persistent_information();
+                    }
                     client_state
                         .other_client_states
-                        .insert(*entity, Some(state.clone()));
+                        .insert(*entity, Some(state));
                     return;
                 }
             });
@@ -149,3 +171,20 @@ pub fn update_client_states(
     // Updating the score
     client_state.score = lobby_state.score.clone();
 }
+
+/// Enemies are hidden if no one of the team can see them. Walls block the line of sight,
+/// and enemies in cover (e.g. forest) are only seen from close by
+fn is_hidden(layer_grid: &LayerGrid, enemy_position: Vec3, team_positions: &[Vec3]) -> bool {
+    let in_cover = layer_grid
+        .get_below(enemy_position)
+        .is_some_and(|tile_layers| tile_layers.hides_tanks);
+    // Without anyone of the team on the map, only cover hides enemies
+    if team_positions.is_empty() {
+        return in_cover;
+    }
+
+    team_positions.iter().all(|position| {
+        (in_cover && position.distance(enemy_position) > SPOT_DISTANCE_IN_COVER)
+            || !layer_grid.has_line_of_sight(*position, enemy_position)
+    })
+}
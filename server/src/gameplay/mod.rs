@@ -9,10 +9,14 @@ use system_sets::MyGameplaySet;
 use tick_systems::TickSystemsPlugin;
 
 pub mod capture_the_flag;
+pub mod command_results;
+pub mod end_lobby;
 pub mod game_state_handling;
 pub mod handle_collisions;
+pub mod handle_obstacles;
 pub mod handle_players;
 pub mod lobby_cleanup;
+pub mod pause_lobby;
 pub mod process_messages;
 pub mod process_messages_when_lobby_not_ready;
 pub mod setup_map;
@@ -72,6 +76,11 @@ fn add_observers_to_lobby(trigger: Trigger<OnAdd, MyLobby>, mut commands: Comman
         .observe(game_state_handling::update_lobby_state)
         .observe(start_lobby::check_if_lobby_should_start)
         .observe(start_lobby::start_lobby)
+        .observe(end_lobby::check_if_lobby_should_end)
+        .observe(end_lobby::end_lobby)
+        .observe(pause_lobby::pause_lobby)
+        .observe(pause_lobby::resume_lobby)
         .observe(process_messages::process_lobby_messages)
+        .observe(handle_obstacles::destroy_obstacles)
         .observe(lobby_cleanup::cleanup_entities);
 }
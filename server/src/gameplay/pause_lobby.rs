@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use shared::networking::{
+    lobby_management::{
+        lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
+        LobbyState,
+    },
+    messages::{
+        message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+        message_data::text_data::TextDataWrapper,
+        message_queue::ImmediateOutMessageQueue,
+    },
+};
+
+/// Stops the targeted lobby from ticking. Tick based timers (cooldowns, respawns, ...) hold still as well
+#[derive(Debug, Event)]
+pub struct PauseLobbyTrigger;
+
+/// Lets the targeted paused lobby tick again
+#[derive(Debug, Event)]
+pub struct ResumeLobbyTrigger;
+
+pub fn pause_lobby(
+    trigger: Trigger<PauseLobbyTrigger>,
+    mut lobby_management: LobbyManagementSystemParam,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let lobby_entity = trigger.entity();
+    let (_, mut lobby, game_state) = lobby_management
+        .get_lobby_mut(lobby_entity)
+        .expect("Failed to get lobby");
+
+    if lobby.state != LobbyState::InProgress {
+        warn!(
+            "Lobby \"{}\" can't be paused, it is in state {:?}",
+            lobby.lobby_name, lobby.state
+        );
+        return;
+    }
+    lobby.state = LobbyState::Paused;
+
+    info!(
+        "Paused lobby \"{}\" on tick {}",
+        lobby.lobby_name, game_state.tick
+    );
+    let message = NetworkMessageType::LobbyPaused(TextDataWrapper::new(format!(
+        "The game got paused on tick {}",
+        game_state.tick
+    )));
+    send_to_everyone_in_lobby(&lobby_management, lobby_entity, message, &mut queues);
+}
+
+pub fn resume_lobby(
+    trigger: Trigger<ResumeLobbyTrigger>,
+    mut lobby_management: LobbyManagementSystemParam,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let lobby_entity = trigger.entity();
+    let (_, mut lobby, game_state) = lobby_management
+        .get_lobby_mut(lobby_entity)
+        .expect("Failed to get lobby");
+
+    if lobby.state != LobbyState::Paused {
+        warn!(
+            "Lobby \"{}\" can't be resumed, it is in state {:?}",
+            lobby.lobby_name, lobby.state
+        );
+        return;
+    }
+    lobby.state = LobbyState::InProgress;
+
+    info!(
+        "Resumed lobby \"{}\" on tick {}",
+        lobby.lobby_name, game_state.tick
+    );
+    let message = NetworkMessageType::LobbyResumed(TextDataWrapper::new(format!(
+        "The game continues on tick {}",
+        game_state.tick
+    )));
+    send_to_everyone_in_lobby(&lobby_management, lobby_entity, message, &mut queues);
+}
+
+fn send_to_everyone_in_lobby(
+    lobby_management: &LobbyManagementSystemParam,
+    lobby_entity: Entity,
+    message: NetworkMessageType,
+    queues: &mut Query<&mut ImmediateOutMessageQueue>,
+) {
+    let clients = lobby_management
+        .targets_get_players_and_spectators_in_lobby(LobbyManagementArgument {
+            lobby: Some(lobby_entity),
+            ..default()
+        })
+        .unwrap_or_default();
+
+    for client in clients {
+        if let Ok(mut queue) = queues.get_mut(client) {
+            queue.push_back(MessageContainer::new(
+                MessageTarget::Client(client),
+                message.clone(),
+            ));
+        }
+    }
+}
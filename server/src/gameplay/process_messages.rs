@@ -8,6 +8,9 @@ use shared::{
         },
         messages::{
             message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+            message_data::{
+                command_results::CommandRejectionReason, message_error_types::ErrorMessageTypes,
+            },
             message_queue::{ImmediateOutMessageQueue, OutMessageQueue},
         },
     },
@@ -15,7 +18,7 @@ use shared::{
 
 use crate::gameplay::triggers::MovePorjectilesSimulationStepTrigger;
 
-use super::triggers::CollectAndTriggerMessagesTrigger;
+use super::{command_results::CommandResults, triggers::CollectAndTriggerMessagesTrigger};
 
 pub fn process_lobby_messages(
     trigger: Trigger<CollectAndTriggerMessagesTrigger>,
@@ -23,31 +26,24 @@ pub fn process_lobby_messages(
     mut commands: Commands,
     mut outgoing_message_queues: Query<&mut OutMessageQueue>,
     mut immediate_message_queues: Query<&mut ImmediateOutMessageQueue>,
-    client: Query<(&InTeam, Option<&PlayerState>)>,
+    client: Query<(Option<&InTeam>, Option<&PlayerState>)>,
+    mut command_results: Query<&mut CommandResults>,
 ) {
     let lobby_entity = trigger.entity();
 
-    let messages_to_process = lobby_management
+    let (_, mut lobby, current_lobby_state) = lobby_management
         .get_lobby_mut(lobby_entity)
-        .expect("Lobby not found")
-        .1
+        .expect("Lobby not found");
+    let current_tick = current_lobby_state.tick;
+
+    // Messages scheduled for a later tick stay in the queue until then
+    let (messages_to_process, scheduled_messages): (Vec<_>, Vec<_>) = lobby
         .messages
         .drain(..)
-        .collect::<Vec<_>>();
-
-    let current_lobby_state = lobby_management
-        .get_lobby_gamestate(lobby_entity)
-        .expect("Lobby Gamestate not found");
+        .partition(|message_container| message_container.tick_to_be_processed_at <= current_tick);
+    lobby.messages.extend(scheduled_messages);
 
     for message_container in messages_to_process {
-        if message_container.tick_to_be_processed_at > current_lobby_state.tick {
-            warn!(
-                "Dropping message because it's too old: {:?}",
-                message_container
-            );
-            continue;
-        }
-
         let (client_team_name, player_state) = client
             .get(message_container.sender.expect("Message sender not found"))
             .expect("Client not found");
@@ -59,7 +55,7 @@ pub fn process_lobby_messages(
                 MessageTarget::Client(e) => Some(e),
                 _ => None,
             },
-            team_name: Some(client_team_name.0.clone()),
+            team_name: client_team_name.map(|team| team.0.clone()),
             sender_state: player_state.cloned(),
         };
 
@@ -71,6 +67,20 @@ pub fn process_lobby_messages(
         );
 
         if let Err(e) = result {
+            if let Ok(mut command_results) = command_results.get_mut(sender) {
+                let reason = match &e {
+                    ErrorMessageTypes::InvalidSenderState(_) => {
+                        CommandRejectionReason::WrongPlayerState
+                    }
+                    _ => CommandRejectionReason::Invalid,
+                };
+                command_results.reject(
+                    message_container.message.clone(),
+                    reason,
+                    format!("{:?}", e),
+                );
+            }
+
             error!(
                 "Failed to handle message before lobby is ready:\n\tError: {:?}\n\tMessage: {:?}",
                 e, message_container
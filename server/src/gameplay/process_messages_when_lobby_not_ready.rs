@@ -6,6 +6,7 @@ use shared::networking::{
     },
     messages::{
         message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+        message_data::message_error_types::ErrorMessageTypes,
         message_queue::{ImmediateOutMessageQueue, OutMessageQueue},
     },
 };
@@ -47,15 +48,43 @@ pub fn process_messages_before_lobby_is_ready(
             .drain(..)
             .collect::<Vec<_>>();
 
-        let (_, lobby, _) = lobby_management
+        let (_, lobby, lobby_state) = lobby_management
             .get_lobby(*lobby_entity)
             .expect("Lobby not found");
+        // Messages for later ticks of a paused lobby wait until it resumes
+        let mut scheduled_messages = Vec::new();
 
         for message_container in messages_to_process {
             match message_container.message {
-                NetworkMessageType::StartGame(_) => {
+                NetworkMessageType::StartGame(_)
+                | NetworkMessageType::RequestTournamentStandings(_)
+                | NetworkMessageType::RequestRatings(_) => {
                     // received messages that are allowed before lobby is ready
                 }
+                _ if lobby.state == LobbyState::Paused
+                    && message_container.tick_to_be_processed_at > lobby_state.tick + 1 =>
+                {
+                    scheduled_messages.push(message_container);
+                    continue;
+                }
+                _ if lobby.state == LobbyState::Paused => {
+                    // Commands due while the game is paused get rejected, so the bots know they didn't do anything
+                    if let Some(sender) = message_container.sender {
+                        if let Ok(mut error_queue) = immediate_message_queues.get_mut(sender) {
+                            error_queue.push_back(MessageContainer::new(
+                                MessageTarget::Client(sender),
+                                NetworkMessageType::MessageError(ErrorMessageTypes::LobbyPaused(
+                                    format!(
+                                        "Lobby \"{}\" is paused, the message got dropped",
+                                        lobby.lobby_name
+                                    ),
+                                )),
+                            ));
+                        }
+                    }
+
+                    continue;
+                }
                 _ => {
                     // Received unhandled message, skip it
                     warn!(
@@ -102,5 +131,12 @@ pub fn process_messages_before_lobby_is_ready(
                 ));
             }
         }
+
+        lobby_management
+            .get_lobby_mut(*lobby_entity)
+            .expect("Lobby not found")
+            .1
+            .messages
+            .extend(scheduled_messages);
     }
 }
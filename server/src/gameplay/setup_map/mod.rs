@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use shared::networking::lobby_management::MyLobby;
 
 pub mod setup_flags;
+pub mod setup_obstacles;
 
 pub struct MySetupMapPlugin;
 
@@ -14,5 +15,6 @@ impl Plugin for MySetupMapPlugin {
 fn add_observers_to_lobby(trigger: Trigger<OnAdd, MyLobby>, mut commands: Commands) {
     commands
         .entity(trigger.entity())
-        .observe(setup_flags::setup_flags);
+        .observe(setup_flags::setup_flags)
+        .observe(setup_obstacles::setup_obstacles);
 }
@@ -23,6 +23,14 @@ pub fn setup_flags(
 
     let mut new_bases = Vec::new();
     let mut new_flags = Vec::new();
+    // The radii of the flag rules replace the horizontal size of the colliders
+    let flag_rules = &lobby.flag_rules;
+    let flag_base_half_size = FLAG_BASE_HALF_SIZE
+        .with_x(flag_rules.capture_radius)
+        .with_z(flag_rules.capture_radius);
+    let flag_half_size = FLAG_HALF_SIZE
+        .with_x(flag_rules.pickup_radius)
+        .with_z(flag_rules.pickup_radius);
     if let Some(map_config) = &lobby.map_config {
         let team_names = map_config.get_team_names();
         let map = &map_config.map;
@@ -55,7 +63,7 @@ pub fn setup_flags(
                         Name::new(format!("FlagBase_{}_{}", my_team, flag_number)),
                         WantedTransform(Transform::from_translation(marker_position)),
                         Collider {
-                            half_size: FLAG_BASE_HALF_SIZE,
+                            half_size: flag_base_half_size,
                             max_slope: 0.0,
                             height_offset: FLAG_BASE_HALF_SIZE.y,
                         },
@@ -75,7 +83,7 @@ pub fn setup_flags(
                         FlagState::InBase,
                         WantedTransform(Transform::from_translation(marker_position)),
                         Collider {
-                            half_size: FLAG_HALF_SIZE,
+                            half_size: flag_half_size,
                             max_slope: 0.0,
                             height_offset: FLAG_HALF_SIZE.y,
                         },
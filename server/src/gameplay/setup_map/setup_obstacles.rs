@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use shared::{
+    asset_handling::maps::MarkerType,
+    game::{
+        collision_handling::components::{Collider, CollisionLayer, WantedTransform},
+        obstacle::ObstacleMarker,
+        player_handling::Health,
+    },
+    networking::lobby_management::{InLobby, MyLobby},
+};
+
+use crate::gameplay::triggers::InitAllObstaclesTrigger;
+
+pub fn setup_obstacles(
+    trigger: Trigger<InitAllObstaclesTrigger>,
+    mut commands: Commands,
+    mut my_lobby: Query<&mut MyLobby>,
+) {
+    let lobby_id = trigger.entity();
+    let mut lobby = my_lobby.get_mut(lobby_id).expect("Lobby not found");
+
+    let mut new_obstacles = Vec::new();
+    if let Some(map_config) = &lobby.map_config {
+        let map = &map_config.map;
+        for (marker_index, marker) in map.markers.iter().enumerate() {
+            let MarkerType::Obstacle {
+                obstacle_type,
+                health,
+            } = marker.kind
+            else {
+                continue;
+            };
+            let marker_position = map
+                .get_real_world_position_of_tile(marker.tile.clone())
+                .expect("Failed to get real world position of tile");
+            let half_size = obstacle_type.half_size();
+
+            let new_obstacle = commands
+                .spawn((
+                    Name::new(format!("Obstacle_{:?}_{}", obstacle_type, marker_index)),
+                    InLobby(lobby_id),
+                    ObstacleMarker {
+                        obstacle_type,
+                        tile: marker.tile.clone(),
+                    },
+                    Health::new(health.unwrap_or(obstacle_type.default_health())),
+                    WantedTransform(Transform::from_translation(marker_position)),
+                    Collider {
+                        half_size,
+                        max_slope: 0.0,
+                        height_offset: half_size.y,
+                    },
+                    // Only projectiles collide with it, tanks are kept off its tile by the world collision
+                    CollisionLayer::obstacle(),
+                ))
+                .id();
+            new_obstacles.push(new_obstacle);
+        }
+    }
+    lobby.obstacles.extend(new_obstacles);
+}
@@ -1,11 +1,16 @@
+use std::time::Duration;
+
 use bevy::{prelude::*, utils::HashMap};
 use shared::{
-    asset_handling::config::{ServerConfigSystemParam, TankConfigSystemParam},
+    asset_handling::{
+        config::{ServerConfigSystemParam, TankConfigSystemParam},
+        maps::MapConfig,
+    },
     game::{player_handling::TankBodyMarker, tank_types::TankType},
     networking::{
         lobby_management::{
             lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
-            InLobby, InTeam, LobbyState, MyLobby,
+            GameMode, InLobby, InTeam, LobbyState, MyLobby,
         },
         messages::{
             message_container::{
@@ -21,13 +26,17 @@ use shared::{
     },
 };
 
-use crate::networking::handle_clients::lib::MyNetworkClient;
+use crate::{networking::handle_clients::lib::MyNetworkClient, rating::ratings::MyRatings};
 
 use super::{
     capture_the_flag::triggers::InitAllFlagsTrigger,
     handle_players::{dummy_handling::DummyClientMarker, handle_spawning::RespawnPlayerTrigger},
+    triggers::InitAllObstaclesTrigger,
 };
 
+/// Without dummies, free-for-all lobbies need at least this many players to start
+const FREE_FOR_ALL_MIN_PLAYERS: usize = 2;
+
 #[derive(Debug, Event)]
 pub struct StartLobbyTrigger;
 
@@ -41,98 +50,115 @@ pub fn check_if_lobby_should_start(
     let lobby_entity = trigger.entity();
     let start_config = &(**trigger.event());
     let mut lobby = lobbies.get_mut(lobby_entity).expect("Failed to get lobby");
-    let sender = trigger.sender.unwrap();
-    let mut sender_queue = client_queues
-        .get_mut(sender)
-        .expect("Failed to get queue for sender");
+    // No sender means the server itself wants to start the lobby (e.g. a tournament match)
+    let sender = trigger.sender;
 
     if lobby.state != LobbyState::ReadyToStart {
-        sender_queue.push_back(MessageContainer::new(
-            MessageTarget::Client(sender),
-            NetworkMessageType::MessageError(ErrorMessageTypes::LobbyNotReadyToStart(format!(
+        send_start_error(
+            sender,
+            ErrorMessageTypes::LobbyNotReadyToStart(format!(
                 "Lobby is not ready to start: {:?}",
                 lobby.state
-            ))),
-        ));
+            )),
+            &mut client_queues,
+        );
+
+        return;
+    }
+
+    if let Err(error) = start_config.flag_rules.validate() {
+        send_start_error(
+            sender,
+            ErrorMessageTypes::LobbyNotReadyToStart(format!("Invalid flag rules: {}", error)),
+            &mut client_queues,
+        );
 
         return;
     }
 
-    // Assign every player, that hasn't already, a spawnpoint.
+    lobby.max_ticks = start_config.max_ticks;
+    lobby.lockstep_timer = start_config
+        .lockstep_timeout
+        .map(|timeout| Timer::new(Duration::from_millis(timeout), TimerMode::Once));
+    lobby.fast_forward = start_config.fast_forward;
+    lobby.flag_rules = start_config.flag_rules.clone();
+
+    // Assign every player a spawn point of their team. Players that asked for one get it, if it is free
     let map_config = lobby.map_config.as_mut().expect("Failed to get map config");
-    // Team name -> set of taken spawn point ids
+    // Team name -> taken spawn point ids
     let mut taken_spawn_points_team: HashMap<String, Vec<usize>> = HashMap::new();
-    for (team_name, team) in map_config.teams.iter() {
-        let spawn_points = map_config
-            .map
-            .get_all_spawn_points_of_group(&team_name)
-            .iter()
-            .map(|(_, id)| *id)
-            .collect::<Vec<_>>();
-        let mut clients_without_spawn_points = Vec::new();
-
-        for player in team.players.iter() {
-            let client = clients.get(*player).expect("Failed to get client");
-            if let Some(assigned_spawn_point) = client.assigned_spawn_point {
-                taken_spawn_points_team
-                    .entry(team_name.clone())
-                    .or_insert_with(Vec::new)
-                    .push(assigned_spawn_point);
-                info!(
-                    "Player {:?} already has spawn point {:?}",
-                    client.name, assigned_spawn_point
-                );
-            } else {
-                clients_without_spawn_points.push(*player);
-            }
+    let mut team_names = map_config.get_team_names();
+    team_names.sort();
+    for team_name in team_names.iter() {
+        let team = &map_config.teams[team_name];
+        let spawn_points = spawn_points_of_team(map_config, team_name);
+        if spawn_points.is_empty() && !team.players.is_empty() {
+            send_start_error(
+                sender,
+                ErrorMessageTypes::LobbyNotReadyToStart(format!(
+                    "Team {} has no spawn points",
+                    team_name
+                )),
+                &mut client_queues,
+            );
+
+            return;
         }
 
-        for client in clients_without_spawn_points {
-            // Find the first spawn point that is not taken for this team,
-            // or just take the first one if all available spawn points are already assigned
-            let taken_points = taken_spawn_points_team
-                .entry(team_name.clone())
-                .or_insert_with(Vec::new);
+        // Players that asked for a spawn point pick first
+        let mut players = team.players.clone();
+        players.sort_by_key(|player| {
+            clients
+                .get(*player)
+                .map_or(true, |client| client.assigned_spawn_point.is_none())
+        });
 
-            let spawn_point = spawn_points
-                .iter()
-                .find(|spawn_point| !taken_points.contains(spawn_point))
-                .unwrap_or(&spawn_points[0]);
+        let taken_points = taken_spawn_points_team
+            .entry(team_name.clone())
+            .or_insert_with(Vec::new);
+        for player in players {
+            let mut client = clients.get_mut(player).expect("Failed to get client");
+            let spawn_point =
+                pick_spawn_point(&spawn_points, taken_points, client.assigned_spawn_point);
+            if let Some(wanted) = client
+                .assigned_spawn_point
+                .filter(|wanted| Some(*wanted) != spawn_point)
+            {
+                info!(
+                    "Spawn point {} of player {:?} in team {} is taken or doesn't exist, using {:?}",
+                    wanted, client.name, team_name, spawn_point
+                );
+            }
 
-            let mut client = clients.get_mut(client).expect("Failed to get client");
-            client.assigned_spawn_point = Some(*spawn_point);
-            taken_points.push(*spawn_point);
+            client.assigned_spawn_point = spawn_point;
+            taken_points.extend(spawn_point);
         }
     }
 
     if start_config.fill_empty_slots_with_dummies {
         // go through all teams, and if they have less players than max, fill them with dummies
         let mut dummy_players = Vec::new();
-        for (team_name, team) in map_config.teams.iter_mut() {
-            let needed_players = team.max_players - team.players.len();
-            let spawn_points = map_config
-                .map
-                .get_all_spawn_points_of_group(&team_name)
-                .iter()
-                .map(|(_, id)| *id)
-                .collect::<Vec<_>>();
+        for team_name in team_names.iter() {
+            let spawn_points = spawn_points_of_team(map_config, team_name);
+            let team = map_config
+                .teams
+                .get_mut(team_name)
+                .expect("Failed to get team");
+            let needed_players = team.max_players.saturating_sub(team.players.len());
+            let taken_points = taken_spawn_points_team
+                .entry(team_name.clone())
+                .or_insert_with(Vec::new);
 
             for i in 0..needed_players {
+                let Some(spawn_point) = pick_spawn_point(&spawn_points, taken_points, None) else {
+                    warn!("Team {} has no spawn points, not adding dummies", team_name);
+                    break;
+                };
                 let dummy_name = format!("{}-dummy-{}", team_name, i);
-                // Find the first spawn point that is not taken for this team,
-                // or just take the first one if all available spawn points are already assigned
-                let taken_points = taken_spawn_points_team
-                    .entry(team_name.clone())
-                    .or_insert_with(Vec::new);
-
-                let spawn_point = spawn_points
-                    .iter()
-                    .find(|spawn_point| !taken_points.contains(spawn_point))
-                    .unwrap_or(&spawn_points[0]);
 
                 let mut dummy_client = MyNetworkClient::new_dummy(dummy_name.clone());
-                dummy_client.assigned_spawn_point = Some(*spawn_point);
-                taken_points.push(*spawn_point);
+                dummy_client.assigned_spawn_point = Some(spawn_point);
+                taken_points.push(spawn_point);
 
                 let client_type = ClientType::Dummy;
                 let tank_type = TankType::LightTank; // TODO: Randomly select tank type? better would be if we could set up a lobby with specific dummies
@@ -155,31 +181,97 @@ pub fn check_if_lobby_should_start(
         }
         lobby.players.extend(dummy_players);
     } else {
-        let needed_players = lobby
+        let slots = lobby
             .map_config
             .as_ref()
             .expect("Failed to get map config")
             .teams
             .iter()
             .fold(0, |acc, (_, team)| acc + team.max_players);
+        // Free-for-all lobbies can start as soon as two players fight each other
+        let needed_players = match lobby.game_mode {
+            GameMode::Teams => slots,
+            GameMode::FreeForAll => slots.min(FREE_FOR_ALL_MIN_PLAYERS),
+        };
 
         if lobby.players.len() < needed_players {
-            sender_queue.push_back(MessageContainer::new(
-                MessageTarget::Client(sender),
-                NetworkMessageType::MessageError(ErrorMessageTypes::LobbyNotReadyToStart(format!(
+            send_start_error(
+                sender,
+                ErrorMessageTypes::LobbyNotReadyToStart(format!(
                     "Not enough players in lobby: {} < {}",
                     lobby.players.len(),
                     needed_players
-                ))),
-            ));
+                )),
+                &mut client_queues,
+            );
 
             return;
         }
     }
 
+    if lobby.game_mode == GameMode::FreeForAll {
+        // Flags of teams nobody joined could be taken without anyone defending them
+        lobby
+            .map_config
+            .as_mut()
+            .expect("Failed to get map config")
+            .remove_empty_teams();
+    }
+
     commands.trigger_targets(StartLobbyTrigger, lobby_entity);
 }
 
+/// The ids of the spawn points of a team, sorted
+fn spawn_points_of_team(map_config: &MapConfig, team_name: &str) -> Vec<usize> {
+    let mut spawn_points = map_config
+        .map
+        .get_all_spawn_points_of_group(team_name)
+        .iter()
+        .map(|(_, id)| *id)
+        .collect::<Vec<_>>();
+    spawn_points.sort();
+    spawn_points
+}
+
+/// The wanted spawn point if it exists and is free, otherwise the free one with the lowest id.
+/// If all of them are taken, players have to share the first one
+fn pick_spawn_point(
+    spawn_points: &[usize],
+    taken: &[usize],
+    wanted: Option<usize>,
+) -> Option<usize> {
+    wanted
+        .filter(|wanted| spawn_points.contains(wanted) && !taken.contains(wanted))
+        .or_else(|| {
+            spawn_points
+                .iter()
+                .find(|spawn_point| !taken.contains(spawn_point))
+                .copied()
+        })
+        .or_else(|| spawn_points.first().copied())
+}
+
+/// Sends the error back to the client that wanted to start the lobby, or only logs it if the server started the lobby itself
+fn send_start_error(
+    sender: Option<Entity>,
+    error: ErrorMessageTypes,
+    client_queues: &mut Query<&mut ImmediateOutMessageQueue>,
+) {
+    match sender {
+        Some(sender) => {
+            let mut sender_queue = client_queues
+                .get_mut(sender)
+                .expect("Failed to get queue for sender");
+            sender_queue.push_back(MessageContainer::new(
+                MessageTarget::Client(sender),
+                NetworkMessageType::MessageError(error),
+            ));
+        }
+        None => error!("Failed to start lobby: {:?}", error),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn start_lobby(
     trigger: Trigger<StartLobbyTrigger>,
     mut lobby_management: LobbyManagementSystemParam,
@@ -187,11 +279,13 @@ pub fn start_lobby(
     clients: Query<(&MyNetworkClient, &ClientType, Option<&TankType>)>,
     server_config: ServerConfigSystemParam,
     tank_config: TankConfigSystemParam,
+    ratings: Option<Res<MyRatings>>,
     mut commands: Commands,
 ) {
     let lobby_entity = trigger.entity();
     let map;
     let team_configs;
+    let flag_rules;
     {
         let (_, lobby, mut lobby_state) = lobby_management
             .get_lobby_mut(lobby_entity)
@@ -199,17 +293,23 @@ pub fn start_lobby(
         let map_config = lobby.map_config.as_ref().expect("Failed to get map config");
         map = map_config.map.clone();
         team_configs = map_config.teams.clone();
+        flag_rules = lobby.flag_rules.clone();
         let team_names = team_configs.keys().cloned().collect::<Vec<_>>();
         lobby_state.setup_score(team_names);
     }
 
     commands.trigger_targets(InitAllFlagsTrigger, lobby_entity);
+    commands.trigger_targets(InitAllObstaclesTrigger, lobby_entity);
 
     let server_config = server_config.server_config();
     let tank_configs = tank_config.tank_configs();
 
-    let connected_clients =
-        get_connected_configs_in_lobby(&lobby_management, lobby_entity, &clients);
+    let connected_clients = get_connected_configs_in_lobby(
+        &lobby_management,
+        lobby_entity,
+        &clients,
+        ratings.as_deref(),
+    );
     match lobby_management.targets_get_players_and_spectators_in_lobby(LobbyManagementArgument {
         lobby: Some(lobby_entity),
         ..default()
@@ -236,6 +336,7 @@ pub fn start_lobby(
                         map_definition: map.clone(),
                         team_configs: team_configs.clone(),
                         tank_configs: tank_configs.tanks.clone(),
+                        flag_rules: flag_rules.clone(),
                     }),
                 ));
             }
@@ -254,6 +355,7 @@ fn get_connected_configs_in_lobby(
     lobby_management: &LobbyManagementSystemParam,
     lobby_entity: Entity,
     clients: &Query<(&MyNetworkClient, &ClientType, Option<&TankType>)>,
+    ratings: Option<&MyRatings>,
 ) -> Vec<ConnectedClientConfig> {
     lobby_management
         .get_lobby(lobby_entity)
@@ -264,13 +366,23 @@ fn get_connected_configs_in_lobby(
             // Iterate through each team directly
             for (team_name, team) in map_config.teams.iter() {
                 for player in team.players.iter() {
-                    if let Some((client, _client_type, tank_type)) = clients.get(*player).ok() {
+                    if let Some((client, client_type, tank_type)) = clients.get(*player).ok() {
+                        let client_name = client.name.as_ref().unwrap().clone();
+                        // Dummies don't have a rating
+                        let client_rating = match client_type {
+                            ClientType::Player => {
+                                ratings.map(|ratings| ratings.get_rating(&client_name))
+                            }
+                            _ => None,
+                        };
+
                         connected_configs.push(ConnectedClientConfig {
                             client_id: *player,
-                            client_name: client.name.as_ref().unwrap().clone(),
+                            client_name,
                             client_team: team_name.clone(),
                             client_tank_type: tank_type.expect("Failed to get tank type").clone(),
                             assigned_spawn_point: client.assigned_spawn_point.unwrap(),
+                            client_rating,
                         });
                     } else {
                         error!(
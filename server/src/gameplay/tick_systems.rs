@@ -1,20 +1,35 @@
+use std::time::{Duration, Instant};
+
 use bevy::prelude::*;
 use shared::{
     game::game_state::LobbyGameState,
-    networking::lobby_management::{LobbyState, MyLobby},
+    networking::{
+        lobby_management::{LobbyState, MyLobby},
+        messages::{
+            message_container::NetworkMessageType, message_data::first_contact::ClientType,
+        },
+    },
 };
 
-use crate::gameplay::triggers::CollectAndTriggerMessagesTrigger;
+use crate::gameplay::{
+    game_state_handling::check_if_client_states_are_all_up_to_date,
+    triggers::CollectAndTriggerMessagesTrigger,
+};
 
 use super::{system_sets::MyGameplaySet, triggers::StartNextTickProcessingTrigger};
 
+/// How long fast forward lobbies may keep ticking in one frame, leaves room for the rest of a 60 fps frame
+const FAST_FORWARD_FRAME_BUDGET: Duration = Duration::from_millis(12);
+
 pub struct TickSystemsPlugin;
 
 impl Plugin for TickSystemsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (process_tick_timer.in_set(MyGameplaySet::TickTimerProcessing),),
+            (process_tick_timer, run_fast_forward_ticks)
+                .chain()
+                .in_set(MyGameplaySet::TickTimerProcessing),
         )
         .add_observer(add_trigger_to_lobby);
     }
@@ -22,18 +37,117 @@ impl Plugin for TickSystemsPlugin {
 
 fn process_tick_timer(
     mut commands: Commands,
-    mut lobbies: Query<(Entity, &mut MyLobby)>,
+    mut lobbies: Query<(Entity, &mut MyLobby, &LobbyGameState)>,
     time: Res<Time>,
 ) {
-    for (entity, mut lobby) in lobbies.iter_mut() {
-        if LobbyState::InProgress == lobby.state {
-            if lobby.tick_timer.tick(time.delta()).just_finished() {
+    for (entity, mut lobby, game_state) in lobbies.iter_mut() {
+        // Fast forward lobbies are ticked by run_fast_forward_ticks
+        if LobbyState::InProgress == lobby.state && !lobby.fast_forward {
+            let start_next_tick = if lobby.lockstep_timer.is_some() {
+                lockstep_turn_over(&mut lobby, game_state, time.delta())
+            } else {
+                lobby.tick_timer.tick(time.delta()).just_finished()
+            };
+
+            if start_next_tick {
                 commands.trigger_targets(StartNextTickProcessingTrigger, entity);
             }
         }
     }
 }
 
+/// Runs the ticks of fast forward lobbies back to back, as long as the players already ended their turn
+/// (always true for lobbies with only dummies) and the frame budget isn't used up.
+/// Every tick is processed and sent out completely before the next one starts
+fn run_fast_forward_ticks(world: &mut World) {
+    let started = Instant::now();
+    let delta = world.resource::<Time>().delta();
+    let mut lobbies = world.query::<(Entity, &mut MyLobby, &LobbyGameState)>();
+    let fast_forward_lobbies = lobbies
+        .iter(world)
+        .filter(|(_, lobby, _)| lobby.fast_forward && lobby.state == LobbyState::InProgress)
+        .map(|(entity, _, _)| entity)
+        .collect::<Vec<_>>();
+
+    let mut first_round = true;
+    loop {
+        let mut ticked_lobbies = Vec::new();
+        for lobby_entity in fast_forward_lobbies.iter() {
+            let Ok((_, mut lobby, game_state)) = lobbies.get_mut(world, *lobby_entity) else {
+                continue;
+            };
+            // The lobby might have ended, or its last tick isn't sent out yet
+            if lobby.state != LobbyState::InProgress
+                || (!first_round && lobby.tick_processed != game_state.tick)
+            {
+                continue;
+            }
+            // The time of the frame only counts once for the lockstep timeout
+            let delta = if first_round { delta } else { Duration::ZERO };
+            if lockstep_turn_over(&mut lobby, game_state, delta) {
+                ticked_lobbies.push(*lobby_entity);
+            }
+        }
+
+        if ticked_lobbies.is_empty() {
+            break;
+        }
+        for lobby_entity in ticked_lobbies {
+            world.trigger_targets(StartNextTickProcessingTrigger, lobby_entity);
+            world.flush();
+        }
+        if let Err(error) = world.run_system_cached(check_if_client_states_are_all_up_to_date) {
+            error!("Failed to send out the fast forwarded ticks: {}", error);
+            break;
+        }
+        world.flush();
+
+        first_round = false;
+        if started.elapsed() >= FAST_FORWARD_FRAME_BUDGET {
+            break;
+        }
+    }
+}
+
+/// In lockstep, the turn is over once every player sent an EndTurn for the current tick, or the timeout ran out.
+/// Fast forward lobbies without a lockstep timeout wait for the players as long as it takes
+fn lockstep_turn_over(lobby: &mut MyLobby, game_state: &LobbyGameState, delta: Duration) -> bool {
+    let all_turns_ended = lobby
+        .players
+        .iter()
+        .filter(|(_, _, client_type)| *client_type == ClientType::Player)
+        .all(|(_, player, _)| {
+            lobby.messages.iter().any(|message_container| {
+                message_container.sender == Some(*player)
+                    && matches!(
+                        &message_container.message,
+                        NetworkMessageType::EndTurn(end_turn) if end_turn.tick == game_state.tick
+                    )
+            })
+        });
+
+    let timed_out = lobby
+        .lockstep_timer
+        .as_mut()
+        .is_some_and(|timer| timer.tick(delta).finished());
+
+    if all_turns_ended || timed_out {
+        if !all_turns_ended {
+            debug!(
+                "Lobby \"{}\" timed out waiting for all players to end their turn on tick {}",
+                lobby.lobby_name, game_state.tick
+            );
+        }
+
+        if let Some(timer) = lobby.lockstep_timer.as_mut() {
+            timer.reset();
+        }
+        return true;
+    }
+
+    false
+}
+
 fn add_trigger_to_lobby(trigger: Trigger<OnAdd, MyLobby>, mut commands: Commands) {
     commands.entity(trigger.entity()).observe(increment_tick);
 }
@@ -6,6 +6,10 @@ pub struct StartNextTickProcessingTrigger;
 #[derive(Debug, Reflect, Event)]
 pub struct CollectAndTriggerMessagesTrigger;
 
+/// Spawns the obstacles of the map when the lobby starts
+#[derive(Debug, Reflect, Event)]
+pub struct InitAllObstaclesTrigger;
+
 #[derive(Debug, Reflect, Event)]
 pub struct MovePorjectilesSimulationStepTrigger;
 
@@ -1,12 +1,18 @@
+use admin::MyAdminPlugin;
 use bevy::prelude::*;
 use gameplay::MyGameplayPlugin;
 use networking::MyNetworkingPlugin;
+use rating::MyRatingPlugin;
 use shared::MySharedPlugin;
+use tournament::MyTournamentPlugin;
 
+pub mod admin;
 #[cfg(feature = "debug")]
 pub mod debug;
 pub mod gameplay;
 pub mod networking;
+pub mod rating;
+pub mod tournament;
 
 pub struct MyServerPlugin;
 
@@ -33,6 +39,9 @@ impl Plugin for MyServerPlugin {
             MySharedPlugin,
             MyGameplayPlugin,
             MyNetworkingPlugin,
+            MyTournamentPlugin,
+            MyRatingPlugin,
+            MyAdminPlugin,
             #[cfg(feature = "debug")]
             debug::MyServerDebugPlugin,
         ));
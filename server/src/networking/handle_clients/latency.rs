@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use shared::networking::messages::{
+    message_container::{
+        MessageContainer, MessageTarget, NetworkMessageType, PingTrigger, PongTrigger,
+    },
+    message_data::{first_contact::ClientType, ping::PingData},
+    message_queue::ImmediateOutMessageQueue,
+};
+
+use super::lib::MyNetworkClient;
+
+/// How often the server pings every client
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+/// How much a new measurement changes the running estimates
+const SMOOTHING: f32 = 0.2;
+/// The server warns every time this many messages of a client in a row arrived late
+const LATE_MESSAGES_WARNING: u32 = 20;
+
+#[derive(Debug, Resource, Deref, DerefMut)]
+pub struct PingTimer(pub Timer);
+
+impl Default for PingTimer {
+    fn default() -> Self {
+        Self(Timer::new(PING_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+/// Round trip time and tick offset of a connection
+#[derive(Debug, Default, Component)]
+pub struct ConnectionStats {
+    /// Running estimate of the round trip time in milliseconds
+    pub rtt_ms: Option<f32>,
+    /// Running estimate of how many ticks the messages of the client are behind the lobby (tick received - tick sent)
+    pub tick_offset: Option<f32>,
+    /// How many messages in a row were sent for a tick that was already over
+    pub late_messages_in_a_row: u32,
+    /// The last ping that didn't get answered yet. Gets replaced by the next ping, if it never gets answered
+    pending_ping: Option<(u64, Instant)>,
+    next_ping_id: u64,
+}
+
+impl ConnectionStats {
+    pub fn start_ping(&mut self) -> u64 {
+        let id = self.next_ping_id;
+        self.next_ping_id += 1;
+        self.pending_ping = Some((id, Instant::now()));
+        id
+    }
+
+    /// Returns the measured round trip time, if the pong answers the pending ping
+    pub fn record_pong(&mut self, id: u64) -> Option<f32> {
+        match self.pending_ping {
+            Some((pending_id, sent_at)) if pending_id == id => {
+                self.pending_ping = None;
+                let rtt_ms = sent_at.elapsed().as_secs_f32() * 1000.0;
+                self.rtt_ms = Some(smooth(self.rtt_ms, rtt_ms));
+                Some(rtt_ms)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the client keeps sending messages for ticks that are already over
+    pub fn record_tick_offset(&mut self, tick_sent: u64, tick_received: u64) -> bool {
+        let offset = tick_received.saturating_sub(tick_sent);
+        self.tick_offset = Some(smooth(self.tick_offset, offset as f32));
+
+        // Messages sent for the current tick get processed in the next one, anything older missed a tick
+        if offset > 0 {
+            self.late_messages_in_a_row += 1;
+        } else {
+            self.late_messages_in_a_row = 0;
+        }
+
+        self.late_messages_in_a_row > 0
+            && self
+                .late_messages_in_a_row
+                .is_multiple_of(LATE_MESSAGES_WARNING)
+    }
+}
+
+fn smooth(estimate: Option<f32>, measurement: f32) -> f32 {
+    estimate.map_or(measurement, |estimate| {
+        estimate + SMOOTHING * (measurement - estimate)
+    })
+}
+
+/// Pings every client that finished its first contact
+pub fn send_pings(
+    mut timer: ResMut<PingTimer>,
+    time: Res<Time>,
+    mut clients: Query<
+        (
+            Entity,
+            &MyNetworkClient,
+            &mut ConnectionStats,
+            &mut ImmediateOutMessageQueue,
+        ),
+        With<ClientType>,
+    >,
+) {
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for (client_entity, client, mut stats, mut queue) in clients.iter_mut() {
+        if client.stream.is_none() {
+            // Dummies don't need to be pinged
+            continue;
+        }
+
+        let id = stats.start_ping();
+        queue.push_back(MessageContainer::new(
+            MessageTarget::Client(client_entity),
+            NetworkMessageType::Ping(PingData { id }),
+        ));
+    }
+}
+
+pub fn handle_pong(trigger: Trigger<PongTrigger>, mut stats: Query<&mut ConnectionStats>) {
+    let sender = trigger.sender.expect("Failed to get sender");
+    if let Ok(mut stats) = stats.get_mut(sender) {
+        if stats.record_pong(trigger.message.id).is_none() {
+            debug!(
+                "Client {:?} answered ping {} too late",
+                sender, trigger.message.id
+            );
+        }
+    }
+}
+
+/// Clients can ping the server as well, to measure the round trip time themselves
+pub fn handle_ping(
+    trigger: Trigger<PingTrigger>,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let sender = trigger.sender.expect("Failed to get sender");
+    if let Ok(mut queue) = queues.get_mut(sender) {
+        queue.push_back(MessageContainer::new(
+            MessageTarget::Client(sender),
+            NetworkMessageType::Pong(trigger.message.clone()),
+        ));
+    }
+}
@@ -6,8 +6,22 @@ use shared::{
     networking::messages::message_queue::{ImmediateOutMessageQueue, OutMessageQueue},
 };
 
+use crate::{
+    gameplay::command_results::CommandResults,
+    networking::handle_messages::traffic_limits::TrafficStats,
+};
+
+use super::latency::ConnectionStats;
+
 #[derive(Debug, Component)]
-#[require(OutMessageQueue, ImmediateOutMessageQueue, PersonalizedClientGameState)]
+#[require(
+    OutMessageQueue,
+    ImmediateOutMessageQueue,
+    PersonalizedClientGameState,
+    CommandResults,
+    ConnectionStats,
+    TrafficStats
+)]
 pub struct MyNetworkClient {
     pub name: Option<String>,
     pub assigned_spawn_point: Option<usize>,
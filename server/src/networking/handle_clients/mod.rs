@@ -1,20 +1,28 @@
 use bevy::prelude::*;
 use handle_connect::accept_connections_system;
 use handle_disconnect::handle_client_disconnects;
+use latency::PingTimer;
 use shared::networking::networking_system_sets::MyNetworkingSet;
 
 mod handle_connect;
 mod handle_disconnect;
+pub mod latency;
 pub mod lib;
 
 pub struct HandleClientsPlugin;
 
 impl Plugin for HandleClientsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (accept_connections_system,).in_set(MyNetworkingSet::AcceptConnections),
-        )
-        .add_observer(handle_client_disconnects);
+        app.init_resource::<PingTimer>()
+            .add_systems(
+                Update,
+                (
+                    (accept_connections_system,).in_set(MyNetworkingSet::AcceptConnections),
+                    latency::send_pings.in_set(MyNetworkingSet::SendingMessages),
+                ),
+            )
+            .add_observer(handle_client_disconnects)
+            .add_observer(latency::handle_ping)
+            .add_observer(latency::handle_pong);
     }
 }
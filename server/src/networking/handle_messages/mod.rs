@@ -2,9 +2,11 @@ use bevy::prelude::*;
 use receiving_messages::handle_reading_messages;
 use sending_messages::{sending_client_messages, sending_immediate_messages};
 use shared::networking::{lobby_management::MyLobby, networking_system_sets::MyNetworkingSet};
+use traffic_limits::disconnect_penalized_clients;
 
 pub mod receiving_messages;
 pub mod sending_messages;
+pub mod traffic_limits;
 
 pub struct HandleMessagesPlugin;
 
@@ -15,6 +17,7 @@ impl Plugin for HandleMessagesPlugin {
             (
                 handle_reading_messages.in_set(MyNetworkingSet::ReadingMessages),
                 sending_immediate_messages.in_set(MyNetworkingSet::SendingMessages),
+                disconnect_penalized_clients.after(MyNetworkingSet::SendingMessages),
             ),
         )
         .add_observer(add_triggers_to_lobby);
@@ -1,32 +1,63 @@
 use std::io::Read;
 
 use bevy::prelude::*;
-use shared::networking::{
-    lobby_management::{
-        lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
-        InLobby, InTeam,
+use serde_json::value::RawValue;
+use shared::{
+    asset_handling::config::ServerConfigSystemParam,
+    networking::{
+        lobby_management::{
+            lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
+            InLobby, InTeam,
+        },
+        messages::{
+            message_container::{MessageContainer, MessageTarget, NetworkMessageType},
+            message_data::{
+                command_results::CommandRejectionReason,
+                message_error_types::{ErrorMessageTypes, MalformedMessageData},
+            },
+            message_queue::{ImmediateOutMessageQueue, OutMessageQueue},
+        },
     },
-    messages::{
-        message_container::{MessageContainer, MessageTarget, NetworkMessageType},
-        message_queue::{ImmediateOutMessageQueue, OutMessageQueue},
+};
+
+use crate::{
+    gameplay::command_results::CommandResults,
+    networking::handle_clients::{
+        latency::ConnectionStats,
+        lib::{ClientDisconnectedTrigger, MyNetworkClient},
     },
 };
 
-use crate::networking::handle_clients::lib::{ClientDisconnectedTrigger, MyNetworkClient};
+use super::traffic_limits::{Penalty, TrafficStats};
+
+type ReadingClient<'a> = (
+    Entity,
+    &'a mut MyNetworkClient,
+    &'a mut TrafficStats,
+    Option<&'a InLobby>,
+    Option<&'a InTeam>,
+);
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_reading_messages(
     mut commands: Commands,
-    mut clients: Query<(
-        Entity,
-        &mut MyNetworkClient,
-        Option<&InLobby>,
-        Option<&InTeam>,
-    )>,
+    mut clients: Query<ReadingClient>,
     mut outgoing_message_queues: Query<&mut OutMessageQueue>,
     mut immediate_message_queues: Query<&mut ImmediateOutMessageQueue>,
     mut lobby_management: LobbyManagementSystemParam,
+    mut command_results: Query<&mut CommandResults>,
+    mut connection_stats: Query<&mut ConnectionStats>,
+    server_config: ServerConfigSystemParam,
 ) {
-    for (sender, mut network_client, in_lobby, in_team) in clients.iter_mut() {
+    let max_ticks_ahead = server_config.server_config().max_ticks_ahead;
+    let traffic_limits = &server_config.server_config().traffic_limits;
+
+    for (sender, mut network_client, mut traffic_stats, in_lobby, in_team) in clients.iter_mut() {
+        if traffic_stats.disconnect {
+            // Gets disconnected once its last error message was sent
+            continue;
+        }
+
         let addr = network_client.get_address();
         if let Some(stream) = &mut network_client.stream {
             // Read the 4-byte length prefix for the payload length
@@ -45,6 +76,26 @@ pub fn handle_reading_messages(
                 // No message to read
                 continue;
             }
+            if msg_len > traffic_limits.max_frame_size {
+                // The frame can't be skipped without reading it, so there is no way to stay in sync with the stream
+                warn!(
+                    "Client {:?} sent a frame of {} bytes, the maximum is {}. Disconnecting",
+                    addr, msg_len, traffic_limits.max_frame_size
+                );
+                traffic_stats.disconnect = true;
+                if let Ok(mut error_queue) = immediate_message_queues.get_mut(sender) {
+                    error_queue.push_back(MessageContainer::new(
+                        MessageTarget::Client(sender),
+                        NetworkMessageType::MessageError(ErrorMessageTypes::TrafficLimitExceeded(
+                            format!(
+                                "Sent a frame of {} bytes, the maximum is {} bytes. You get disconnected",
+                                msg_len, traffic_limits.max_frame_size
+                            ),
+                        )),
+                    ));
+                }
+                continue;
+            }
 
             // Read the actual message payload into a buffer
             let mut buf = vec![0u8; msg_len];
@@ -67,15 +118,110 @@ pub fn handle_reading_messages(
             };
 
             // Deserialize the JSON into an array of MessageContainers
-            match serde_json::from_str::<Vec<MessageContainer>>(&received) {
-                Ok(mut messages) => {
+            match parse_messages(&received) {
+                Ok((mut messages, malformed_messages)) => {
+                    // The valid messages of the batch still get handled
+                    for malformed_message in malformed_messages {
+                        warn!(
+                            "Failed to parse message {} from {:?}: {}",
+                            malformed_message.path, addr, malformed_message.error
+                        );
+                        send_malformed_message_error(
+                            sender,
+                            malformed_message,
+                            &mut immediate_message_queues,
+                        );
+                    }
+
                     // Clear duplicate messages of types marked as "unique". only keeping the latest one.
                     clear_duplicate_unique_messages(&mut messages);
 
+                    if traffic_stats.is_muted() {
+                        debug!(
+                            "Ignoring {} messages from muted client {:?}",
+                            messages.len(),
+                            addr
+                        );
+                        continue;
+                    }
+
+                    let lobby_tick = in_lobby.map(|in_lobby| {
+                        lobby_management
+                            .get_lobby_gamestate(**in_lobby)
+                            .expect("Failed to get lobby game state")
+                            .tick
+                    });
+                    let (dropped_messages, violations) =
+                        traffic_stats.filter_messages(&mut messages, lobby_tick, traffic_limits);
+                    if !violations.is_empty() {
+                        let penalty = traffic_stats.add_strike(traffic_limits);
+                        warn!(
+                            "Client {:?} ({:?}) broke the traffic limits (strike {}): {}",
+                            network_client.name,
+                            addr,
+                            traffic_stats.strikes,
+                            violations.join(", ")
+                        );
+
+                        if let Ok(mut command_results) = command_results.get_mut(sender) {
+                            for dropped_message in dropped_messages {
+                                command_results.reject(
+                                    dropped_message.message,
+                                    CommandRejectionReason::RateLimited,
+                                    "Dropped for breaking the traffic limits".to_string(),
+                                );
+                            }
+                        }
+                        if let Ok(mut error_queue) = immediate_message_queues.get_mut(sender) {
+                            error_queue.push_back(MessageContainer::new(
+                                MessageTarget::Client(sender),
+                                NetworkMessageType::MessageError(
+                                    ErrorMessageTypes::TrafficLimitExceeded(format!(
+                                        "{}. Strike {} of {}. {}",
+                                        violations.join(". "),
+                                        traffic_stats.strikes,
+                                        traffic_limits.disconnect_after_strikes,
+                                        penalty.describe()
+                                    )),
+                                ),
+                            ));
+                        }
+                        if penalty == Penalty::Disconnect {
+                            info!(
+                                "Disconnecting client {:?} for breaking the traffic limits",
+                                addr
+                            );
+                            continue;
+                        }
+                    }
+
+                    // The newest tick the client knew about when sending, tells how far behind it is
+                    if let (Some(current_tick), Some(tick_sent)) = (
+                        lobby_tick,
+                        messages.iter().map(|message| message.tick_sent).max(),
+                    ) {
+                        if let Ok(mut stats) = connection_stats.get_mut(sender) {
+                            if stats.record_tick_offset(tick_sent, current_tick) {
+                                warn!(
+                                    "Client {:?} ({:?}) keeps missing ticks: its last {} messages were sent for ticks that were already over (about {:.1} ticks behind)",
+                                    network_client.name,
+                                    addr,
+                                    stats.late_messages_in_a_row,
+                                    stats.tick_offset.unwrap_or_default()
+                                );
+                            }
+                        }
+                    }
+
                     for message_container in messages.iter_mut() {
                         message_container.sender = Some(sender);
+                        // Pings have to be answered right away to measure the round trip time, so they never go into the lobby's queue
+                        let is_ping = matches!(
+                            message_container.message,
+                            NetworkMessageType::Ping(_) | NetworkMessageType::Pong(_)
+                        );
                         // If we're in the lobby, add all messages to the lobby's message queue, so we can process them in the correct moment. expecting all non-server-only messages
-                        if let Some(in_lobby) = in_lobby {
+                        if let Some(in_lobby) = in_lobby.filter(|_| !is_ping) {
                             // Set the received tick to the current tick of the lobby
                             message_container.tick_received = lobby_management
                                 .get_lobby_gamestate(**in_lobby)
@@ -83,7 +229,27 @@ pub fn handle_reading_messages(
                                 .expect("Failed to get lobby game state")
                                 .tick;
                             message_container.tick_to_be_processed_at =
-                                message_container.tick_received + 1;
+                                match message_container.tick_target {
+                                    Some(tick_target) => tick_target,
+                                    None => message_container.tick_received + 1,
+                                };
+
+                            if let Err((reason, reason_message)) = check_tick_target(
+                                message_container.tick_to_be_processed_at,
+                                message_container.tick_received,
+                                max_ticks_ahead,
+                            ) {
+                                warn!("Rejecting message from {:?}: {}", addr, reason_message);
+                                if let Ok(mut command_results) = command_results.get_mut(sender) {
+                                    command_results.reject(
+                                        message_container.message.clone(),
+                                        reason,
+                                        reason_message,
+                                    );
+                                }
+
+                                continue;
+                            }
 
                             // Add message to the lobby's message queue
                             lobby_management
@@ -131,12 +297,16 @@ pub fn handle_reading_messages(
                         }
                     }
                 }
-                Err(e) => {
+                Err(malformed_batch) => {
                     error!(
                         "Failed to parse JSON array from {:?}: {}. Raw data: {}",
-                        addr, e, received
+                        addr, malformed_batch.error, received
+                    );
+                    send_malformed_message_error(
+                        sender,
+                        malformed_batch,
+                        &mut immediate_message_queues,
                     );
-                    // TODO add error message to queue
                 }
             }
         } else {
@@ -145,18 +315,126 @@ pub fn handle_reading_messages(
     }
 }
 
+/// Parses the received batch one message at a time, so a single malformed message doesn't throw away the others
+/// Returns the valid messages and the errors of the malformed ones, or an error if the batch isn't a JSON array at all
+fn parse_messages(
+    received: &str,
+) -> Result<(Vec<MessageContainer>, Vec<MalformedMessageData>), MalformedMessageData> {
+    let raw_messages =
+        serde_json::from_str::<Vec<&RawValue>>(received).map_err(|e| MalformedMessageData {
+            line: e.line(),
+            column: e.column(),
+            path: String::new(),
+            error: e.to_string(),
+        })?;
+
+    let mut messages = Vec::new();
+    let mut malformed_messages = Vec::new();
+    for (index, raw_message) in raw_messages.into_iter().enumerate() {
+        match serde_json::from_str::<MessageContainer>(raw_message.get()) {
+            Ok(message) => messages.push(message),
+            Err(e) => {
+                // The raw message is a slice of the received batch, so its offset tells where it starts
+                let start = raw_message.get().as_ptr() as usize - received.as_ptr() as usize;
+                let before = &received[..start];
+                let start_line = before.matches('\n').count() + 1;
+                let start_column = before.len() - before.rfind('\n').map_or(0, |i| i + 1);
+
+                malformed_messages.push(MalformedMessageData {
+                    line: start_line + e.line() - 1,
+                    column: if e.line() == 1 {
+                        start_column + e.column()
+                    } else {
+                        e.column()
+                    },
+                    path: malformed_message_path(index, raw_message),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok((messages, malformed_messages))
+}
+
+/// Narrows down which field of a malformed message is the problem
+fn malformed_message_path(index: usize, raw_message: &RawValue) -> String {
+    let Ok(fields) =
+        serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(raw_message.get())
+    else {
+        return format!("[{}]", index);
+    };
+
+    if let Some(message) = fields.get("message") {
+        if serde_json::from_value::<NetworkMessageType>(message.clone()).is_err() {
+            return format!("[{}].message", index);
+        }
+    }
+    if let Some(target) = fields.get("target") {
+        if serde_json::from_value::<MessageTarget>(target.clone()).is_err() {
+            return format!("[{}].target", index);
+        }
+    }
+
+    format!("[{}]", index)
+}
+
+fn send_malformed_message_error(
+    sender: Entity,
+    malformed_message: MalformedMessageData,
+    immediate_message_queues: &mut Query<&mut ImmediateOutMessageQueue>,
+) {
+    if let Ok(mut error_queue) = immediate_message_queues.get_mut(sender) {
+        error_queue.push_back(MessageContainer::new(
+            MessageTarget::Client(sender),
+            NetworkMessageType::MessageError(ErrorMessageTypes::MalformedMessage(
+                malformed_message,
+            )),
+        ));
+    }
+}
+
+/// A message can only be scheduled for a tick that isn't processed yet, and not too far in the future
+fn check_tick_target(
+    tick_target: u64,
+    current_tick: u64,
+    max_ticks_ahead: u64,
+) -> Result<(), (CommandRejectionReason, String)> {
+    if tick_target <= current_tick {
+        return Err((
+            CommandRejectionReason::StaleTick,
+            format!(
+                "Tick {} is already processed, the lobby is on tick {}",
+                tick_target, current_tick
+            ),
+        ));
+    }
+
+    if tick_target > current_tick + max_ticks_ahead {
+        return Err((
+            CommandRejectionReason::TooFarAhead,
+            format!(
+                "Tick {} is too far ahead, the lobby is on tick {} and messages can be scheduled at most {} ticks ahead",
+                tick_target, current_tick, max_ticks_ahead
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
 // Clear duplicate messages of types marked as "unique". only keeping the latest one.
 // call .is_unique() on the message container directly.
 // store the .message.message (is an enum). then delete any occurring duplicates.
+// The same message scheduled for different ticks is no duplicate, e.g. moving in each of the next ticks.
 // So we have to iterate backwards, so we first get the latest message and then remove the older ones.
 fn clear_duplicate_unique_messages(messages: &mut Vec<MessageContainer>) {
     let mut unique_messages = Vec::new();
     for message in messages.iter().rev() {
         if message.is_unique() {
-            if !unique_messages
-                .iter()
-                .any(|m: &MessageContainer| m.message == message.message)
-            {
+            if !unique_messages.iter().any(|m: &MessageContainer| {
+                m.message == message.message && m.tick_target == message.tick_target
+            }) {
                 unique_messages.push(message.clone());
             }
         } else {
@@ -166,3 +444,71 @@ fn clear_duplicate_unique_messages(messages: &mut Vec<MessageContainer>) {
     unique_messages.reverse();
     *messages = unique_messages;
 }
+
+#[cfg(test)]
+mod tests {
+    use shared::networking::messages::message_data::{
+        tank_messages::move_tank::MoveTankCommand, text_data::TextDataWrapper,
+    };
+
+    use super::*;
+
+    fn move_tank(distance: f32, tick_target: Option<u64>) -> MessageContainer {
+        let mut message = MessageContainer::new(
+            MessageTarget::ToSelf,
+            NetworkMessageType::MoveTankCommand(MoveTankCommand { distance }),
+        );
+        message.tick_target = tick_target;
+        message
+    }
+
+    fn text(message: &str) -> MessageContainer {
+        MessageContainer::new(
+            MessageTarget::AllInLobby,
+            NetworkMessageType::SimpleTextMessage(TextDataWrapper::new(message.to_string())),
+        )
+    }
+
+    #[test]
+    fn keeps_only_the_latest_of_equal_unique_messages() {
+        let mut messages = vec![move_tank(1.0, None), text("a"), move_tank(1.0, None)];
+
+        clear_duplicate_unique_messages(&mut messages);
+
+        assert_eq!(messages, vec![text("a"), move_tank(1.0, None)]);
+    }
+
+    #[test]
+    fn keeps_different_unique_messages_of_the_same_type() {
+        let mut messages = vec![move_tank(1.0, None), move_tank(2.0, None)];
+
+        clear_duplicate_unique_messages(&mut messages);
+
+        assert_eq!(messages, vec![move_tank(1.0, None), move_tank(2.0, None)]);
+    }
+
+    #[test]
+    fn keeps_equal_unique_messages_for_different_ticks() {
+        let mut messages = vec![
+            move_tank(1.0, Some(5)),
+            move_tank(1.0, Some(6)),
+            move_tank(1.0, Some(5)),
+        ];
+
+        clear_duplicate_unique_messages(&mut messages);
+
+        assert_eq!(
+            messages,
+            vec![move_tank(1.0, Some(6)), move_tank(1.0, Some(5))]
+        );
+    }
+
+    #[test]
+    fn keeps_messages_that_are_not_unique() {
+        let mut messages = vec![text("a"), text("a")];
+
+        clear_duplicate_unique_messages(&mut messages);
+
+        assert_eq!(messages, vec![text("a"), text("a")]);
+    }
+}
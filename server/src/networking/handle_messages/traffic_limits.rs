@@ -0,0 +1,182 @@
+use std::time::{Duration, Instant};
+
+use bevy::{prelude::*, utils::HashMap};
+use shared::{
+    asset_handling::config::TrafficLimitsConfig,
+    networking::messages::message_container::MessageContainer,
+};
+
+use crate::networking::handle_clients::lib::ClientDisconnectedTrigger;
+
+/// Clients not in a lobby have no ticks, their messages get counted per second instead
+const WINDOW_WITHOUT_TICKS: Duration = Duration::from_secs(1);
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Counts the messages of a client and the strikes it got for breaking the traffic limits
+#[derive(Debug, Component)]
+pub struct TrafficStats {
+    /// The lobby tick the messages get counted for, None if the client isn't in a lobby
+    window_tick: Option<u64>,
+    window_started: Instant,
+    messages_in_window: usize,
+    /// Per message type, when its window started and how many messages of that type arrived since
+    type_windows: HashMap<&'static str, (Instant, u32)>,
+    pub strikes: u32,
+    last_strike: Option<Instant>,
+    muted_until: Option<Instant>,
+    /// Set once the client has to be disconnected, after its last error message was sent
+    pub disconnect: bool,
+}
+
+impl Default for TrafficStats {
+    fn default() -> Self {
+        Self {
+            window_tick: None,
+            window_started: Instant::now(),
+            messages_in_window: 0,
+            type_windows: HashMap::new(),
+            strikes: 0,
+            last_strike: None,
+            muted_until: None,
+            disconnect: false,
+        }
+    }
+}
+
+/// What happens to a client after it broke a traffic limit
+#[derive(Debug, Clone, PartialEq)]
+pub enum Penalty {
+    Warning { strikes_left: u32 },
+    Muted(Duration),
+    Disconnect,
+}
+
+impl TrafficStats {
+    pub fn is_muted(&self) -> bool {
+        self.disconnect
+            || self
+                .muted_until
+                .is_some_and(|muted_until| Instant::now() < muted_until)
+    }
+
+    /// Removes every message over the limits, returns the dropped messages and why they got dropped
+    pub fn filter_messages(
+        &mut self,
+        messages: &mut Vec<MessageContainer>,
+        lobby_tick: Option<u64>,
+        limits: &TrafficLimitsConfig,
+    ) -> (Vec<MessageContainer>, Vec<String>) {
+        let now = Instant::now();
+        let window_over = match lobby_tick {
+            Some(_) => self.window_tick != lobby_tick,
+            None => now.duration_since(self.window_started) >= WINDOW_WITHOUT_TICKS,
+        };
+        if window_over {
+            self.window_tick = lobby_tick;
+            self.window_started = now;
+            self.messages_in_window = 0;
+        }
+
+        let mut dropped = Vec::new();
+        let mut violations = Vec::new();
+
+        let allowed = limits
+            .max_messages_per_tick
+            .saturating_sub(self.messages_in_window);
+        if messages.len() > allowed {
+            violations.push(format!(
+                "Sent {} messages, only {} messages per tick are allowed. Dropped the last {}",
+                self.messages_in_window + messages.len(),
+                limits.max_messages_per_tick,
+                messages.len() - allowed
+            ));
+            dropped.extend(messages.split_off(allowed));
+        }
+        self.messages_in_window += messages.len();
+
+        let mut dropped_types: HashMap<&'static str, u32> = HashMap::new();
+        let (within_rate, over_rate): (Vec<_>, Vec<_>) = messages.drain(..).partition(|message| {
+            let message_type = message.message_type_name();
+            let Some(rate) = limits.message_type_rates.get(message_type) else {
+                return true;
+            };
+
+            let (window_started, count) = self.type_windows.entry(message_type).or_insert((now, 0));
+            if now.duration_since(*window_started) >= RATE_WINDOW {
+                *window_started = now;
+                *count = 0;
+            }
+
+            if *count < *rate {
+                *count += 1;
+                true
+            } else {
+                *dropped_types.entry(message_type).or_default() += 1;
+                false
+            }
+        });
+        *messages = within_rate;
+        dropped.extend(over_rate);
+        for (message_type, count) in dropped_types {
+            violations.push(format!(
+                "Only {} {} messages per second are allowed. Dropped {}",
+                limits.message_type_rates[message_type], message_type, count
+            ));
+        }
+
+        (dropped, violations)
+    }
+
+    /// Every strike makes the penalty worse, until the client gets disconnected
+    pub fn add_strike(&mut self, limits: &TrafficLimitsConfig) -> Penalty {
+        let now = Instant::now();
+        if self.last_strike.is_some_and(|last_strike| {
+            now.duration_since(last_strike) >= Duration::from_millis(limits.strikes_reset_after)
+        }) {
+            self.strikes = 0;
+        }
+        self.strikes += 1;
+        self.last_strike = Some(now);
+
+        if self.strikes >= limits.disconnect_after_strikes {
+            self.disconnect = true;
+            Penalty::Disconnect
+        } else if self.strikes >= limits.mute_after_strikes {
+            let duration = Duration::from_millis(limits.mute_duration);
+            self.muted_until = Some(now + duration);
+            Penalty::Muted(duration)
+        } else {
+            Penalty::Warning {
+                strikes_left: limits.disconnect_after_strikes - self.strikes,
+            }
+        }
+    }
+}
+
+impl Penalty {
+    pub fn describe(&self) -> String {
+        match self {
+            Penalty::Warning { strikes_left } => format!(
+                "This is a warning, {} more strikes and you get disconnected",
+                strikes_left
+            ),
+            Penalty::Muted(duration) => format!(
+                "All your messages get ignored for the next {} ms",
+                duration.as_millis()
+            ),
+            Penalty::Disconnect => "You get disconnected".to_string(),
+        }
+    }
+}
+
+/// Disconnects the clients that got too many strikes, after their error messages were sent
+pub fn disconnect_penalized_clients(
+    clients: Query<(Entity, &TrafficStats)>,
+    mut commands: Commands,
+) {
+    for (client_entity, stats) in clients.iter() {
+        if stats.disconnect {
+            commands.trigger(ClientDisconnectedTrigger(client_entity));
+        }
+    }
+}
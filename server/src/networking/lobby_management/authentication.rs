@@ -0,0 +1,72 @@
+use bevy::{prelude::*, utils::HashMap};
+use shared::asset_handling::config::{AuthenticationConfig, ServerConfigSystemParam};
+
+/// The bot names and their tokens, loaded from the registry file in the server config
+#[derive(Debug, Resource)]
+pub struct BotRegistry {
+    pub config: AuthenticationConfig,
+    pub tokens: HashMap<String, String>,
+}
+
+impl BotRegistry {
+    pub fn load(config: AuthenticationConfig) -> Self {
+        let tokens = match std::fs::read_to_string(&config.registry_file) {
+            Ok(content) => match serde_json::from_str::<HashMap<String, String>>(&content) {
+                Ok(tokens) => tokens,
+                Err(err) => {
+                    error!(
+                        "Failed to parse bot registry {}: {}",
+                        config.registry_file, err
+                    );
+                    HashMap::new()
+                }
+            },
+            Err(err) => {
+                warn!(
+                    "Failed to read bot registry {}: {}",
+                    config.registry_file, err
+                );
+                HashMap::new()
+            }
+        };
+
+        BotRegistry { config, tokens }
+    }
+
+    /// Checks if the bot is allowed to play under this name, returns the reason if not
+    pub fn authenticate(&self, bot_name: &str, auth_token: Option<&String>) -> Result<(), String> {
+        match (self.tokens.get(bot_name), auth_token) {
+            (Some(token), Some(auth_token)) if tokens_match(token, auth_token) => Ok(()),
+            (Some(_), Some(_)) => Err(format!("Wrong token for bot \"{}\"", bot_name)),
+            (Some(_), None) => Err(format!("Bot \"{}\" needs a token", bot_name)),
+            (None, _) if self.config.require_authentication => Err(format!(
+                "Bot \"{}\" is not in the bot registry of the server",
+                bot_name
+            )),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+/// Compares the tokens in constant time, so how long it takes doesn't tell how much of a guess was right
+pub fn tokens_match(expected: &str, given: &str) -> bool {
+    let given = given.as_bytes();
+    let mut difference = expected.len() ^ given.len();
+    for (i, byte) in expected.bytes().enumerate() {
+        difference |= (byte ^ given.get(i).copied().unwrap_or_default()) as usize;
+    }
+
+    std::hint::black_box(difference) == 0
+}
+
+pub fn load_bot_registry(mut commands: Commands, server_config: ServerConfigSystemParam) {
+    if let Some(authentication_config) = &server_config.server_config().authentication {
+        let registry = BotRegistry::load(authentication_config.clone());
+        info!(
+            "Loaded {} bots from the bot registry {}",
+            registry.tokens.len(),
+            authentication_config.registry_file
+        );
+        commands.insert_resource(registry);
+    }
+}
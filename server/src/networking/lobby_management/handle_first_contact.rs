@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use shared::{
-    asset_handling::config::ServerConfigSystemParam,
+    asset_handling::{config::ServerConfigSystemParam, maps::MapConfigSystemParam},
     game::player_handling::TankBodyMarker,
     networking::{
         lobby_management::{
@@ -11,13 +11,28 @@ use shared::{
             message_container::{
                 FirstContactTrigger, MessageContainer, MessageTarget, NetworkMessageType,
             },
-            message_data::{first_contact::ClientType, message_error_types::ErrorMessageTypes},
+            message_data::{
+                first_contact::ClientType, message_error_types::ErrorMessageTypes,
+                text_data::TextDataWrapper,
+            },
             message_queue::ImmediateOutMessageQueue,
         },
     },
 };
 
-use crate::networking::handle_clients::lib::{ClientDisconnectedTrigger, MyNetworkClient};
+use crate::{
+    networking::{
+        handle_clients::lib::{ClientDisconnectedTrigger, MyNetworkClient},
+        handle_messages::traffic_limits::TrafficStats,
+    },
+    tournament::tournament::MyTournament,
+};
+
+use super::{
+    authentication::{tokens_match, BotRegistry},
+    matchmaking::{MatchmakingQueue, QueuedPlayer},
+    server_info::server_info,
+};
 
 pub fn handle_awaiting_first_contact(
     mut commands: Commands,
@@ -34,17 +49,132 @@ pub fn handle_awaiting_first_contact(
 
 // Proof of concept for handling a message using an observer
 // We can even make targeted ones and only trigger for specific clients!
+#[allow(clippy::too_many_arguments)]
 pub fn handle_first_contact_message(
     trigger: Trigger<FirstContactTrigger>,
     mut commands: Commands,
     mut lobby_management: LobbyManagementSystemParam,
     mut clients: Query<(Entity, &mut MyNetworkClient, &mut ImmediateOutMessageQueue)>,
+    mut traffic_stats: Query<&mut TrafficStats>,
     server_config: ServerConfigSystemParam,
+    tournament: Option<Res<MyTournament>>,
+    mut matchmaking_queue: ResMut<MatchmakingQueue>,
+    bot_registry: Option<Res<BotRegistry>>,
+    map_configs: MapConfigSystemParam,
 ) {
     let server_config = server_config.server_config();
     let message = &trigger.message;
     let sender = trigger.sender.unwrap();
 
+    // Players can only use a name from the registry if they know its token
+    if message.client_type == ClientType::Player {
+        if let Some(bot_registry) = &bot_registry {
+            if let Err(reason) =
+                bot_registry.authenticate(&message.bot_name, message.auth_token.as_ref())
+            {
+                warn!("Client {:?} failed to authenticate: {}", sender, reason);
+                if let Ok((_, _, mut message_queue)) = clients.get_mut(sender) {
+                    message_queue.push_back(MessageContainer::new(
+                        MessageTarget::Client(sender),
+                        NetworkMessageType::MessageError(ErrorMessageTypes::AuthenticationFailed(
+                            reason,
+                        )),
+                    ));
+                }
+                // Every guess costs a new connection
+                disconnect_after_sending(sender, &mut traffic_stats);
+
+                return;
+            }
+        }
+    }
+
+    let mut lobby_name = message.lobby_name.clone();
+    let mut map_name = message.map_name.clone();
+    let mut team_name = message.team_name.clone();
+
+    // Bots taking part in a tournament always get sent to the lobby of their next match
+    if message.client_type == ClientType::Player {
+        if let Some(assignment) = tournament
+            .as_ref()
+            .and_then(|tournament| tournament.get_assignment(&message.bot_name))
+        {
+            info!(
+                "Bot \"{}\" has a tournament match, sending it to lobby \"{}\" on team \"{}\"",
+                message.bot_name, assignment.lobby_name, assignment.team_name
+            );
+            lobby_name = Some(assignment.lobby_name);
+            map_name = Some(assignment.map_name);
+            team_name = Some(assignment.team_name);
+        }
+    }
+
+    // Admins don't join a lobby, they only have to know the admin secret
+    if message.client_type == ClientType::Admin {
+        let Ok((_, mut client, mut message_queue)) = clients.get_mut(sender) else {
+            return;
+        };
+
+        let answer = match &server_config.admin_secret {
+            Some(secret)
+                if message
+                    .auth_token
+                    .as_ref()
+                    .is_some_and(|auth_token| tokens_match(secret, auth_token)) =>
+            {
+                info!("Client {:?} authenticated as admin", sender);
+                client.name = Some(message.bot_name.clone());
+                commands
+                    .entity(sender)
+                    .insert((
+                        ClientType::Admin,
+                        Name::new(format!("{:?}_{}", message.client_type, message.bot_name)),
+                    ))
+                    .remove::<AwaitingFirstContact>();
+
+                NetworkMessageType::AdminCommandDone(TextDataWrapper::new(
+                    "Authenticated as admin".to_string(),
+                ))
+            }
+            Some(_) => {
+                warn!("Client {:?} sent a wrong admin secret", sender);
+                disconnect_after_sending(sender, &mut traffic_stats);
+                NetworkMessageType::MessageError(ErrorMessageTypes::AuthenticationFailed(
+                    "Wrong admin secret".to_string(),
+                ))
+            }
+            None => NetworkMessageType::MessageError(ErrorMessageTypes::AuthenticationFailed(
+                "Admin clients are disabled on this server".to_string(),
+            )),
+        };
+
+        message_queue.push_back(MessageContainer::new(MessageTarget::Client(sender), answer));
+        return;
+    }
+
+    // Observers don't join a lobby, they get the server info right away
+    if message.client_type == ClientType::Observer {
+        let Ok((_, mut client, mut message_queue)) = clients.get_mut(sender) else {
+            return;
+        };
+
+        info!("Client {:?} connected as observer", sender);
+        client.name = Some(message.bot_name.clone());
+        commands
+            .entity(sender)
+            .insert((
+                ClientType::Observer,
+                Name::new(format!("{:?}_{}", message.client_type, message.bot_name)),
+            ))
+            .remove::<AwaitingFirstContact>();
+
+        message_queue.push_back(MessageContainer::new(
+            MessageTarget::Client(sender),
+            NetworkMessageType::ServerInfo(server_info(&map_configs, &lobby_management)),
+        ));
+        return;
+    }
+
     // Update the client's state
     if let Ok((client_entity, mut client, mut message_queue)) = clients.get_mut(sender) {
         client.name = Some(message.bot_name.clone());
@@ -89,13 +219,60 @@ pub fn handle_first_contact_message(
                 // We should never receive a dummy client here
                 error!("Received a dummy client in first contact message");
             }
+            ClientType::Admin | ClientType::Observer => {
+                // Admins and observers got handled above
+            }
         }
     }
 
+    let Some(lobby_name) = lobby_name else {
+        let Ok((_, _, mut message_queue)) = clients.get_mut(sender) else {
+            return;
+        };
+
+        match (&message.client_type, &message.tank_type) {
+            (ClientType::Player, Some(tank_type)) if server_config.matchmaking.is_some() => {
+                info!("Queueing bot \"{}\" for matchmaking", message.bot_name);
+                matchmaking_queue
+                    .queued
+                    .retain(|queued_player| queued_player.entity != sender);
+                matchmaking_queue.queued.push(QueuedPlayer {
+                    entity: sender,
+                    name: message.bot_name.clone(),
+                    tank_type: tank_type.clone(),
+                });
+                commands.entity(sender).remove::<AwaitingFirstContact>();
+
+                message_queue.push_back(MessageContainer::new(
+                    MessageTarget::Client(sender),
+                    NetworkMessageType::QueuedForMatchmaking(TextDataWrapper::new(format!(
+                        "Waiting in the matchmaking queue, {} players queued",
+                        matchmaking_queue.queued.len()
+                    ))),
+                ));
+            }
+            _ => {
+                error!(
+                    "Client \"{}\" did not name a lobby and can't be matched up",
+                    message.bot_name
+                );
+                message_queue.push_back(MessageContainer::new(
+                    MessageTarget::Client(sender),
+                    NetworkMessageType::MessageError(ErrorMessageTypes::InvalidFirstContact(
+                        "No lobby name given. Only players can leave it empty, and only if matchmaking is enabled on the server".to_string(),
+                    )),
+                ));
+            }
+        }
+
+        return;
+    };
+
     // get or insert lobby
     match lobby_management.get_or_insert_lobby_entity(
-        &message.lobby_name,
-        message.map_name.as_deref(),
+        &lobby_name,
+        map_name.as_deref(),
+        message.game_mode.unwrap_or_default(),
         &mut commands,
         server_config,
     ) {
@@ -104,7 +281,7 @@ pub fn handle_first_contact_message(
                 player: sender,
                 lobby: lobby_entity,
                 player_type: message.client_type.clone(),
-                team_name: message.team_name.clone(),
+                team_name,
                 player_name: message.bot_name.clone(),
             });
         }
@@ -116,3 +293,10 @@ pub fn handle_first_contact_message(
         }
     }
 }
+
+/// The client gets disconnected once its error message was sent
+fn disconnect_after_sending(client: Entity, traffic_stats: &mut Query<&mut TrafficStats>) {
+    if let Ok(mut stats) = traffic_stats.get_mut(client) {
+        stats.disconnect = true;
+    }
+}
@@ -0,0 +1,305 @@
+use bevy::{prelude::*, utils::HashMap};
+use shared::{
+    asset_handling::{
+        config::{ServerConfigSystemParam, TeamBalancing},
+        maps::{MapConfig, MapConfigSystemParam},
+    },
+    game::tank_types::TankType,
+    networking::{
+        lobby_management::{
+            lobby_management::LobbyManagementSystemParam, GameMode, LobbyState,
+            PlayerWantsToJoinLobbyTrigger,
+        },
+        messages::{
+            message_container::StartGameTrigger,
+            message_data::{first_contact::ClientType, start_game_config::StartGameConfig},
+        },
+    },
+};
+
+use crate::{
+    networking::handle_clients::lib::ClientDisconnectedTrigger, rating::ratings::MyRatings,
+};
+
+#[derive(Debug, Default, Resource)]
+pub struct MatchmakingQueue {
+    /// Players waiting for a match, in the order they got queued
+    pub queued: Vec<QueuedPlayer>,
+    /// Lobbies created by matchmaking that still have to be started
+    pub pending_lobbies: Vec<Entity>,
+    /// Index into the map rotation
+    next_map: usize,
+    lobbies_created: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuedPlayer {
+    pub entity: Entity,
+    pub name: String,
+    pub tank_type: TankType,
+}
+
+pub fn remove_disconnected_player_from_queue(
+    trigger: Trigger<ClientDisconnectedTrigger>,
+    mut queue: ResMut<MatchmakingQueue>,
+) {
+    let disconnected = **trigger.event();
+    queue.queued.retain(|player| player.entity != disconnected);
+}
+
+/// Creates a lobby on the next map of the rotation as soon as enough players are queued
+pub fn match_queued_players(
+    mut queue: ResMut<MatchmakingQueue>,
+    mut lobby_management: LobbyManagementSystemParam,
+    map_config: MapConfigSystemParam,
+    server_config: ServerConfigSystemParam,
+    ratings: Option<Res<MyRatings>>,
+    mut commands: Commands,
+) {
+    let server_config = server_config.server_config();
+    let Some(matchmaking_config) = &server_config.matchmaking else {
+        return;
+    };
+
+    if queue.queued.is_empty() || matchmaking_config.map_rotation.is_empty() {
+        return;
+    }
+
+    let map_name =
+        &matchmaking_config.map_rotation[queue.next_map % matchmaking_config.map_rotation.len()];
+    let Some(map) = map_config.get_map_config_from_name(map_name) else {
+        error!("Matchmaking map \"{}\" not found, skipping it", map_name);
+        queue.next_map += 1;
+        return;
+    };
+
+    let slots = map
+        .teams
+        .values()
+        .map(|team| team.max_players)
+        .sum::<usize>();
+    if slots == 0 {
+        error!(
+            "Matchmaking map \"{}\" has no player slots, skipping it",
+            map_name
+        );
+        queue.next_map += 1;
+        return;
+    }
+    let needed_players = if matchmaking_config.fill_empty_slots_with_dummies {
+        matchmaking_config.min_players.clamp(1, slots)
+    } else {
+        slots
+    };
+
+    if queue.queued.len() < needed_players {
+        return;
+    }
+
+    let player_count = queue.queued.len().min(slots);
+    // The players only leave the queue if they all got a team
+    let Some(assigned_players) = balance_teams(
+        queue.queued[..player_count].to_vec(),
+        map,
+        &matchmaking_config.team_balancing,
+        ratings.as_deref(),
+    ) else {
+        error!(
+            "The teams of matchmaking map \"{}\" have no room for {} players, skipping it",
+            map_name, player_count
+        );
+        queue.next_map += 1;
+        return;
+    };
+    queue.queued.drain(..player_count);
+
+    let mut lobby_name = format!("matchmaking-{}", queue.lobbies_created);
+    while lobby_management
+        .lobby_resource
+        .lobbies
+        .contains_key(&lobby_name)
+    {
+        queue.lobbies_created += 1;
+        lobby_name = format!("matchmaking-{}", queue.lobbies_created);
+    }
+    queue.lobbies_created += 1;
+    queue.next_map += 1;
+
+    match lobby_management.get_or_insert_lobby_entity(
+        &lobby_name,
+        Some(map_name),
+        GameMode::Teams,
+        &mut commands,
+        server_config,
+    ) {
+        Ok(lobby_entity) => {
+            info!(
+                "Matched {} players into lobby \"{}\" on map \"{}\"",
+                assigned_players.len(),
+                lobby_name,
+                map_name
+            );
+
+            for (player, team_name) in assigned_players {
+                commands.trigger(PlayerWantsToJoinLobbyTrigger {
+                    player: player.entity,
+                    player_name: player.name,
+                    lobby: lobby_entity,
+                    player_type: ClientType::Player,
+                    team_name: Some(team_name),
+                });
+            }
+
+            queue.pending_lobbies.push(lobby_entity);
+        }
+        Err(_) => {
+            error!("Failed to create matchmaking lobby \"{}\"", lobby_name);
+        }
+    }
+}
+
+/// Starts the lobbies created by matchmaking once they are set up, so no one has to press "Start Game".
+/// Tries again every frame until the lobby actually started
+pub fn start_matchmaking_lobbies(
+    mut queue: ResMut<MatchmakingQueue>,
+    lobby_management: LobbyManagementSystemParam,
+    server_config: ServerConfigSystemParam,
+    mut commands: Commands,
+) {
+    let server_config = server_config.server_config();
+    let Some(matchmaking_config) = &server_config.matchmaking else {
+        return;
+    };
+
+    queue.pending_lobbies.retain(|lobby_entity| {
+        let Ok((_, lobby, _)) = lobby_management.get_lobby(*lobby_entity) else {
+            warn!(
+                "Matchmaking lobby {} is gone before it could be started",
+                lobby_entity
+            );
+            return false;
+        };
+
+        match lobby.state {
+            LobbyState::SettingUp => return true,
+            LobbyState::ReadyToStart => {}
+            // Started, so it doesn't need us anymore
+            LobbyState::InProgress | LobbyState::Paused | LobbyState::Finished => return false,
+        }
+        if lobby.players.is_empty() {
+            return true;
+        }
+
+        info!("Starting matchmaking lobby \"{}\"", lobby.lobby_name);
+        commands.trigger_targets(
+            StartGameTrigger {
+                message: StartGameConfig {
+                    fill_empty_slots_with_dummies: matchmaking_config.fill_empty_slots_with_dummies,
+                    max_ticks: matchmaking_config.max_ticks,
+                    lockstep_timeout: None,
+                    fast_forward: false,
+                    flag_rules: matchmaking_config.flag_rules.clone(),
+                },
+                sender: None,
+            },
+            *lobby_entity,
+        );
+
+        // The start can still be rejected, so the lobby stays pending until it left ReadyToStart
+        true
+    });
+}
+
+/// Assigns each player a team. Always fills up the team with the fewest players first,
+/// except when balancing by rating, then the team with the lowest total rating gets the next player.
+/// None if the teams don't have enough room for all players
+fn balance_teams(
+    players: Vec<QueuedPlayer>,
+    map: &MapConfig,
+    team_balancing: &TeamBalancing,
+    ratings: Option<&MyRatings>,
+) -> Option<Vec<(QueuedPlayer, String)>> {
+    let mut team_names = map.get_team_names();
+    team_names.sort();
+    let mut team_sizes: HashMap<String, usize> =
+        team_names.iter().map(|team| (team.clone(), 0)).collect();
+
+    if *team_balancing == TeamBalancing::Rating {
+        if let Some(ratings) = ratings {
+            return balance_teams_by_rating(players, map, &team_names, ratings);
+        }
+        warn!("Matchmaking should balance teams by rating, but ratings are disabled");
+    }
+
+    let ordered_players = match team_balancing {
+        TeamBalancing::None | TeamBalancing::Rating => players,
+        TeamBalancing::TankType => {
+            // Grouping players by tank type, so each type gets spread over the teams one after the other
+            let mut tank_types: Vec<TankType> = Vec::new();
+            for player in players.iter() {
+                if !tank_types.contains(&player.tank_type) {
+                    tank_types.push(player.tank_type.clone());
+                }
+            }
+
+            tank_types
+                .iter()
+                .flat_map(|tank_type| {
+                    players
+                        .iter()
+                        .filter(move |player| player.tank_type == *tank_type)
+                        .cloned()
+                })
+                .collect()
+        }
+    };
+
+    ordered_players
+        .into_iter()
+        .map(|player| {
+            let team_name = team_names
+                .iter()
+                .filter(|team| team_sizes[*team] < map.teams[*team].max_players)
+                .min_by_key(|team| team_sizes[*team])?
+                .clone();
+            *team_sizes.get_mut(&team_name).unwrap() += 1;
+
+            Some((player, team_name))
+        })
+        .collect()
+}
+
+/// Best player first, each one joins the team with the lowest total rating that still has room
+fn balance_teams_by_rating(
+    mut players: Vec<QueuedPlayer>,
+    map: &MapConfig,
+    team_names: &[String],
+    ratings: &MyRatings,
+) -> Option<Vec<(QueuedPlayer, String)>> {
+    players.sort_by(|a, b| {
+        ratings
+            .get_rating(&b.name)
+            .total_cmp(&ratings.get_rating(&a.name))
+    });
+
+    let mut teams: HashMap<String, (usize, f32)> = team_names
+        .iter()
+        .map(|team| (team.clone(), (0, 0.0)))
+        .collect();
+
+    players
+        .into_iter()
+        .map(|player| {
+            let team_name = team_names
+                .iter()
+                .filter(|team| teams[*team].0 < map.teams[*team].max_players)
+                .min_by(|a, b| teams[*a].1.total_cmp(&teams[*b].1))?
+                .clone();
+            let team = teams.get_mut(&team_name).unwrap();
+            team.0 += 1;
+            team.1 += ratings.get_rating(&player.name);
+
+            Some((player, team_name))
+        })
+        .collect()
+}
@@ -1,16 +1,35 @@
 use bevy::prelude::*;
 use handle_first_contact::{handle_awaiting_first_contact, handle_first_contact_message};
-use shared::networking::networking_state::MyNetworkingState;
+use matchmaking::MatchmakingQueue;
+use shared::{main_state::MyMainState, networking::networking_state::MyNetworkingState};
 
+pub mod authentication;
 pub mod handle_first_contact;
+pub mod matchmaking;
+pub mod reload_maps;
+pub mod server_info;
 
 pub struct MyLobbyManagementPlugin;
 
 impl Plugin for MyLobbyManagementPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(handle_first_contact_message).add_systems(
-            Update,
-            (handle_awaiting_first_contact.run_if(in_state(MyNetworkingState::Running)),),
-        );
+        app.init_resource::<MatchmakingQueue>()
+            .add_observer(handle_first_contact_message)
+            .add_observer(matchmaking::remove_disconnected_player_from_queue)
+            .add_observer(server_info::handle_server_info_request)
+            .add_systems(
+                OnEnter(MyMainState::Ready),
+                authentication::load_bot_registry,
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_awaiting_first_contact,
+                    matchmaking::match_queued_players,
+                    matchmaking::start_matchmaking_lobbies,
+                    reload_maps::reload_maps_of_idle_lobbies,
+                )
+                    .run_if(in_state(MyNetworkingState::Running)),
+            );
     }
 }
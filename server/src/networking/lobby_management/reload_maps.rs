@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use shared::{
+    asset_handling::maps::{MapConfig, MapConfigSystemParam},
+    networking::lobby_management::{
+        lobby_management::LobbyManagementSystemParam, GameMode, LobbyState,
+    },
+};
+
+/// Applies changed map files to the lobbies that haven't started yet.
+/// The players keep their teams, unless a team got removed or doesn't have enough slots anymore
+pub fn reload_maps_of_idle_lobbies(
+    mut events: EventReader<AssetEvent<MapConfig>>,
+    map_configs: MapConfigSystemParam,
+    mut lobby_management: LobbyManagementSystemParam,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let (Some(map_name), Some(new_map_config)) = (
+            map_configs.get_map_name_from_asset_id(*id),
+            map_configs.get_map_config_from_asset_id(*id),
+        ) else {
+            continue;
+        };
+
+        if let Err(errors) = new_map_config.validate() {
+            error!(
+                "Map \"{}\" changed, but is invalid, keeping the old version:\n\t{}",
+                map_name,
+                errors
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n\t")
+            );
+            continue;
+        }
+
+        for (_, mut lobby, _) in lobby_management.lobby_entities.iter_mut() {
+            if lobby.map_name != map_name
+                || !matches!(
+                    lobby.state,
+                    LobbyState::SettingUp | LobbyState::ReadyToStart
+                )
+            {
+                continue;
+            }
+            let Some(old_map_config) = &lobby.map_config else {
+                continue;
+            };
+
+            let mut map_config = new_map_config.clone();
+            if lobby.game_mode == GameMode::FreeForAll {
+                map_config.make_free_for_all();
+            }
+            let mut fits = true;
+            for (team_name, old_team) in old_map_config.teams.iter() {
+                if old_team.players.is_empty() {
+                    continue;
+                }
+                match map_config.teams.get_mut(team_name) {
+                    Some(team) if team.max_players >= old_team.players.len() => {
+                        team.players = old_team.players.clone();
+                    }
+                    _ => {
+                        fits = false;
+                        break;
+                    }
+                }
+            }
+
+            if !fits {
+                warn!(
+                    "Map \"{}\" changed, but its teams don't fit the players of lobby \"{}\" anymore, keeping the old version",
+                    map_name, lobby.lobby_name
+                );
+                continue;
+            }
+
+            info!(
+                "Reloaded map \"{}\" for lobby \"{}\"",
+                map_name, lobby.lobby_name
+            );
+            lobby.set_map_config(map_config);
+        }
+    }
+}
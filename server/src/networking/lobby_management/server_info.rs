@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use shared::{
+    asset_handling::maps::{MapConfig, MapConfigSystemParam},
+    networking::{
+        lobby_management::{lobby_management::LobbyManagementSystemParam, LobbyState},
+        messages::{
+            message_container::{
+                MessageContainer, MessageTarget, NetworkMessageType, RequestServerInfoTrigger,
+            },
+            message_data::server_info::{LobbySummary, MapInfo, ServerInfoData, TeamSlots},
+            message_queue::ImmediateOutMessageQueue,
+        },
+    },
+};
+
+pub fn handle_server_info_request(
+    trigger: Trigger<RequestServerInfoTrigger>,
+    map_configs: MapConfigSystemParam,
+    lobby_management: LobbyManagementSystemParam,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let sender = trigger.sender.expect("Failed to get sender");
+    let mut queue = queues
+        .get_mut(sender)
+        .expect("Failed to get queue for sender");
+
+    queue.push_back(MessageContainer::new(
+        MessageTarget::Client(sender),
+        NetworkMessageType::ServerInfo(server_info(&map_configs, &lobby_management)),
+    ));
+}
+
+pub fn server_info(
+    map_configs: &MapConfigSystemParam,
+    lobby_management: &LobbyManagementSystemParam,
+) -> ServerInfoData {
+    let mut maps = map_configs
+        .list_map_names()
+        .into_iter()
+        .filter_map(|map_name| {
+            let map_config = map_configs.get_map_config_from_name(&map_name)?;
+            Some(MapInfo {
+                width: map_config.map.width,
+                depth: map_config.map.depth,
+                teams: team_slots(map_config),
+                map_name,
+            })
+        })
+        .collect::<Vec<_>>();
+    maps.sort_by(|a, b| a.map_name.cmp(&b.map_name));
+
+    let mut lobbies = lobby_management
+        .lobby_entities
+        .iter()
+        .map(|(_, lobby, game_state)| LobbySummary {
+            lobby_name: lobby.lobby_name.clone(),
+            map_name: lobby.map_name.clone(),
+            game_mode: lobby.game_mode,
+            state: lobby.state.clone(),
+            joinable: matches!(
+                lobby.state,
+                LobbyState::SettingUp | LobbyState::ReadyToStart
+            ),
+            tick: game_state.tick,
+            spectators: lobby.spectators.len(),
+            teams: lobby
+                .map_config
+                .as_ref()
+                .map(team_slots)
+                .unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    lobbies.sort_by(|a, b| a.lobby_name.cmp(&b.lobby_name));
+
+    ServerInfoData { maps, lobbies }
+}
+
+fn team_slots(map_config: &MapConfig) -> Vec<TeamSlots> {
+    let mut teams = map_config
+        .teams
+        .iter()
+        .map(|(team_name, team)| TeamSlots {
+            team_name: team_name.clone(),
+            max_players: team.max_players,
+            free_slots: team.max_players.saturating_sub(team.players.len()),
+        })
+        .collect::<Vec<_>>();
+    teams.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+
+    teams
+}
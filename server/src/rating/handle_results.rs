@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use shared::networking::{
+    lobby_management::lobby_management::LobbyManagementSystemParam,
+    messages::message_data::first_contact::ClientType,
+};
+
+use crate::gameplay::end_lobby::LobbyFinishedTrigger;
+
+use super::ratings::{MyRatings, RatedTeam};
+
+/// Updates the ratings of all bots in the finished lobby.
+/// Only games where at least two teams had a real bot in them are rated
+pub fn update_ratings_on_game_finished(
+    trigger: Trigger<LobbyFinishedTrigger>,
+    ratings: Option<ResMut<MyRatings>>,
+    lobby_management: LobbyManagementSystemParam,
+) {
+    let Some(mut ratings) = ratings else {
+        return;
+    };
+    let LobbyFinishedTrigger { lobby, result } = trigger.event();
+
+    let Ok((_, lobby, _)) = lobby_management.get_lobby(*lobby) else {
+        error!("Failed to get finished lobby, can't update ratings");
+        return;
+    };
+    let Some(map_config) = &lobby.map_config else {
+        return;
+    };
+
+    let teams = map_config
+        .teams
+        .iter()
+        .map(|(team_name, team)| {
+            let mut bots = Vec::new();
+            let mut dummies = 0;
+            for player in team.players.iter() {
+                match lobby.players.iter().find(|(_, entity, _)| entity == player) {
+                    Some((name, _, ClientType::Player)) => bots.push(name.clone()),
+                    _ => dummies += 1,
+                }
+            }
+
+            RatedTeam {
+                bots,
+                dummies,
+                score: *result.score.get(team_name).unwrap_or(&0),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if teams.iter().filter(|team| !team.bots.is_empty()).count() < 2 {
+        info!(
+            "Not rating the game in lobby \"{}\", less than two teams had a bot in them",
+            result.lobby_name
+        );
+        return;
+    }
+
+    ratings.update_from_game(&teams);
+    ratings.save();
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+use ratings::MyRatings;
+use shared::{asset_handling::config::ServerConfigSystemParam, main_state::MyMainState};
+
+pub mod handle_results;
+pub mod ratings;
+pub mod ratings_request;
+
+pub struct MyRatingPlugin;
+
+impl Plugin for MyRatingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MyMainState::Ready), load_ratings)
+            .add_observer(handle_results::update_ratings_on_game_finished)
+            .add_observer(ratings_request::handle_ratings_request);
+    }
+}
+
+fn load_ratings(mut commands: Commands, server_config: ServerConfigSystemParam) {
+    if let Some(rating_config) = &server_config.server_config().rating {
+        let ratings = MyRatings::load(rating_config.clone());
+        info!(
+            "Loaded {} ratings from {}",
+            ratings.ratings.len(),
+            rating_config.file
+        );
+        commands.insert_resource(ratings);
+    }
+}
@@ -0,0 +1,148 @@
+use bevy::{prelude::*, utils::HashMap};
+use shared::{
+    asset_handling::config::RatingConfig,
+    networking::messages::message_data::rating::{BotRating, RatingsData},
+};
+
+#[derive(Debug, Resource)]
+pub struct MyRatings {
+    pub config: RatingConfig,
+    pub ratings: HashMap<String, BotRating>,
+}
+
+/// A team taking part in a rated game
+#[derive(Debug)]
+pub struct RatedTeam {
+    /// The bots (not the dummies) playing in this team
+    pub bots: Vec<String>,
+    /// How many dummies filled up the team
+    pub dummies: usize,
+    pub score: u32,
+}
+
+impl MyRatings {
+    /// Loads the ratings from the configured file. Starts without any ratings if the file doesn't exist yet
+    pub fn load(config: RatingConfig) -> Self {
+        let ratings = match std::fs::read_to_string(&config.file) {
+            Ok(content) => match serde_json::from_str::<Vec<BotRating>>(&content) {
+                Ok(ratings) => ratings
+                    .into_iter()
+                    .map(|rating| (rating.bot_name.clone(), rating))
+                    .collect(),
+                Err(err) => {
+                    error!("Failed to parse ratings from {}: {}", config.file, err);
+                    HashMap::new()
+                }
+            },
+            Err(_) => HashMap::new(),
+        };
+
+        MyRatings { config, ratings }
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(&self.sorted_ratings()) {
+            Ok(json) => {
+                if let Err(err) = std::fs::write(&self.config.file, json) {
+                    error!("Failed to write ratings to {}: {}", self.config.file, err);
+                }
+            }
+            Err(err) => error!("Failed to serialize ratings: {}", err),
+        }
+    }
+
+    pub fn get_rating(&self, bot_name: &str) -> f32 {
+        self.ratings
+            .get(bot_name)
+            .map(|rating| rating.rating)
+            .unwrap_or(self.config.initial_rating)
+    }
+
+    /// Best bot first
+    pub fn sorted_ratings(&self) -> Vec<BotRating> {
+        let mut ratings = self.ratings.values().cloned().collect::<Vec<_>>();
+        ratings.sort_by(|a, b| {
+            b.rating
+                .total_cmp(&a.rating)
+                .then(a.bot_name.cmp(&b.bot_name))
+        });
+        ratings
+    }
+
+    pub fn ratings_data(&self, bot_names: Option<&Vec<String>>) -> RatingsData {
+        RatingsData {
+            ratings: self
+                .sorted_ratings()
+                .into_iter()
+                .filter(|rating| {
+                    bot_names.is_none_or(|bot_names| bot_names.contains(&rating.bot_name))
+                })
+                .collect(),
+        }
+    }
+
+    /// Team based Elo: the rating of a team is the average rating of its members (dummies count with the initial rating).
+    /// Every team plays against every other team, the expected and actual results get averaged over all opponents.
+    /// All bots of a team get the same rating change.
+    pub fn update_from_game(&mut self, teams: &[RatedTeam]) {
+        let team_ratings = teams
+            .iter()
+            .map(|team| {
+                let total = team
+                    .bots
+                    .iter()
+                    .map(|bot| self.get_rating(bot))
+                    .sum::<f32>()
+                    + team.dummies as f32 * self.config.initial_rating;
+                total / (team.bots.len() + team.dummies).max(1) as f32
+            })
+            .collect::<Vec<_>>();
+
+        let opponents = (teams.len() - 1).max(1) as f32;
+        for (team_index, team) in teams.iter().enumerate() {
+            let mut actual = 0.0;
+            let mut expected = 0.0;
+            for (other_index, other_team) in teams.iter().enumerate() {
+                if other_index == team_index {
+                    continue;
+                }
+
+                expected += 1.0
+                    / (1.0
+                        + 10f32
+                            .powf((team_ratings[other_index] - team_ratings[team_index]) / 400.0));
+                actual += match team.score.cmp(&other_team.score) {
+                    std::cmp::Ordering::Greater => 1.0,
+                    std::cmp::Ordering::Equal => 0.5,
+                    std::cmp::Ordering::Less => 0.0,
+                };
+            }
+
+            let change = self.config.k_factor * (actual - expected) / opponents;
+            let best_score = teams.iter().map(|team| team.score).max().unwrap_or(0);
+            let is_draw = teams
+                .iter()
+                .filter(|other_team| other_team.score == best_score)
+                .count()
+                > 1;
+
+            for bot in team.bots.iter() {
+                let initial_rating = self.config.initial_rating;
+                let rating = self
+                    .ratings
+                    .entry(bot.clone())
+                    .or_insert_with(|| BotRating::new(bot.clone(), initial_rating));
+                rating.rating += change;
+                rating.games_played += 1;
+
+                if team.score == best_score && is_draw {
+                    rating.draws += 1;
+                } else if team.score == best_score {
+                    rating.wins += 1;
+                } else {
+                    rating.losses += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use shared::networking::messages::{
+    message_container::{
+        MessageContainer, MessageTarget, NetworkMessageType, RequestRatingsTrigger,
+    },
+    message_data::message_error_types::ErrorMessageTypes,
+    message_queue::ImmediateOutMessageQueue,
+};
+
+use super::ratings::MyRatings;
+
+pub fn handle_ratings_request(
+    trigger: Trigger<RequestRatingsTrigger>,
+    ratings: Option<Res<MyRatings>>,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let sender = trigger.sender.expect("Failed to get sender");
+    let mut queue = queues
+        .get_mut(sender)
+        .expect("Failed to get queue for sender");
+
+    let message = match ratings {
+        Some(ratings) => {
+            NetworkMessageType::Ratings(ratings.ratings_data(trigger.message.bot_names.as_ref()))
+        }
+        None => NetworkMessageType::MessageError(ErrorMessageTypes::RatingError(
+            "This server doesn't keep ratings".to_string(),
+        )),
+    };
+
+    queue.push_back(MessageContainer::new(
+        MessageTarget::Client(sender),
+        message,
+    ));
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use shared::networking::{
+    lobby_management::{lobby_management::LobbyManagementSystemParam, LobbyState},
+    messages::{
+        message_container::StartGameTrigger,
+        message_data::{
+            first_contact::ClientType, start_game_config::StartGameConfig,
+            tournament::TournamentMatchState,
+        },
+    },
+};
+
+use super::tournament::MyTournament;
+
+/// Starts every scheduled match as soon as all of its bots joined the lobby
+pub fn start_matches_when_all_bots_joined(
+    mut tournament: ResMut<MyTournament>,
+    lobby_management: LobbyManagementSystemParam,
+    mut commands: Commands,
+) {
+    let fill_empty_slots_with_dummies = tournament.config.fill_empty_slots_with_dummies;
+    let max_ticks = tournament.config.max_ticks;
+    let flag_rules = tournament.config.flag_rules.clone();
+
+    for tournament_match in tournament.matches.iter_mut() {
+        let lobby = lobby_management
+            .lobby_resource
+            .lobbies
+            .get(&tournament_match.lobby_name)
+            .and_then(|lobby_entity| lobby_management.get_lobby(*lobby_entity).ok());
+
+        match tournament_match.state {
+            TournamentMatchState::Scheduled => {
+                let Some((lobby_entity, lobby, _)) = lobby else {
+                    continue;
+                };
+
+                if lobby.state != LobbyState::ReadyToStart {
+                    continue;
+                }
+
+                let all_joined = tournament_match.participants.values().all(|bot| {
+                    lobby.players.iter().any(|(name, _, client_type)| {
+                        name == bot && *client_type == ClientType::Player
+                    })
+                });
+
+                if all_joined {
+                    info!(
+                        "All bots joined tournament match {} in lobby \"{}\", starting it",
+                        tournament_match.match_id, tournament_match.lobby_name
+                    );
+                    tournament_match.state = TournamentMatchState::InProgress;
+                    commands.trigger_targets(
+                        StartGameTrigger {
+                            message: StartGameConfig {
+                                fill_empty_slots_with_dummies,
+                                max_ticks: Some(max_ticks),
+                                lockstep_timeout: None,
+                                fast_forward: false,
+                                flag_rules: flag_rules.clone(),
+                            },
+                            sender: None,
+                        },
+                        lobby_entity,
+                    );
+                }
+            }
+            TournamentMatchState::InProgress => {
+                // If everyone left the lobby before the match finished, it has to be played again
+                if lobby.is_none() {
+                    warn!(
+                        "Lobby \"{}\" of tournament match {} is gone before the match finished, rescheduling it",
+                        tournament_match.lobby_name, tournament_match.match_id
+                    );
+                    tournament_match.state = TournamentMatchState::Scheduled;
+                }
+            }
+            TournamentMatchState::Finished => {}
+        }
+    }
+}
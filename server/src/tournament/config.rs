@@ -0,0 +1,47 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_asset_loader::asset_collection::AssetCollection;
+use serde::Deserialize;
+use shared::{
+    game::flag::FlagRules, networking::messages::message_data::tournament::TournamentMode,
+};
+
+#[derive(Debug, Default, Reflect, Resource, Clone, AssetCollection)]
+#[reflect(Resource)]
+pub struct MyTournamentConfigAsset {
+    #[asset(path = "config/config.tournament.ron")]
+    tournament: Handle<TournamentConfig>,
+}
+
+#[derive(Debug, Default, Reflect, Clone, Asset, Deserialize)]
+pub struct TournamentConfig {
+    /// If false, the server runs without a tournament
+    pub enabled: bool,
+    /// Used as a prefix for the names of the lobbies created for the matches
+    pub name: String,
+    pub mode: TournamentMode,
+    /// The names of the bots taking part. In single elimination, the order is the seeding
+    pub bots: Vec<String>,
+    /// The maps to play on. Round robin plays every pairing on every map, single elimination uses one map per round
+    pub maps: Vec<String>,
+    /// How many ticks each match lasts
+    pub max_ticks: u64,
+    pub fill_empty_slots_with_dummies: bool,
+    #[serde(default)]
+    pub flag_rules: FlagRules,
+    /// If set, the standings get written to this file (as JSON) after every match
+    pub standings_file: Option<String>,
+}
+
+#[derive(SystemParam)]
+pub struct TournamentConfigSystemParam<'w> {
+    config_asset: Res<'w, MyTournamentConfigAsset>,
+    tournament_configs: Res<'w, Assets<TournamentConfig>>,
+}
+
+impl<'w> TournamentConfigSystemParam<'w> {
+    pub fn tournament_config(&self) -> &TournamentConfig {
+        self.tournament_configs
+            .get(self.config_asset.tournament.id())
+            .expect("Tournament config not loaded")
+    }
+}
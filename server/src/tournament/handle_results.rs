@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use shared::networking::{
+    lobby_management::lobby_management::LobbyManagementSystemParam,
+    messages::message_data::first_contact::ClientType,
+};
+
+use crate::{
+    gameplay::end_lobby::LobbyFinishedTrigger,
+    networking::handle_clients::lib::ClientDisconnectedTrigger,
+};
+
+use super::tournament::MyTournament;
+
+/// Stores the result of a finished tournament match.
+/// The bots get disconnected afterwards, they have to connect again to play their next match
+pub fn handle_tournament_match_finished(
+    trigger: Trigger<LobbyFinishedTrigger>,
+    tournament: Option<ResMut<MyTournament>>,
+    lobby_management: LobbyManagementSystemParam,
+    mut commands: Commands,
+) {
+    let Some(mut tournament) = tournament else {
+        return;
+    };
+    let LobbyFinishedTrigger { lobby, result } = trigger.event();
+
+    if !tournament.record_result(result) {
+        return;
+    }
+
+    info!(
+        "Recorded result of tournament match in lobby \"{}\": {:?}",
+        result.lobby_name, result.score
+    );
+    tournament.save_standings();
+
+    if let Ok((_, lobby, _)) = lobby_management.get_lobby(*lobby) {
+        for (_, player, client_type) in lobby.players.iter() {
+            if *client_type == ClientType::Player {
+                commands.trigger(ClientDisconnectedTrigger(*player));
+            }
+        }
+    }
+}
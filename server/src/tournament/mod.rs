@@ -0,0 +1,95 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_asset_loader::loading_state::{
+    config::{ConfigureLoadingState, LoadingStateConfig},
+    LoadingStateAppExt,
+};
+use bevy_common_assets::ron::RonAssetPlugin;
+use config::{MyTournamentConfigAsset, TournamentConfig, TournamentConfigSystemParam};
+use shared::{asset_handling::maps::MapConfigSystemParam, main_state::MyMainState};
+use tournament::MyTournament;
+
+pub mod auto_start;
+pub mod config;
+pub mod handle_results;
+pub mod standings_request;
+pub mod tournament;
+
+pub struct MyTournamentPlugin;
+
+impl Plugin for MyTournamentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<TournamentConfig>::new(&["tournament.ron"]))
+            .register_type::<MyTournamentConfigAsset>()
+            .register_type::<TournamentConfig>()
+            .configure_loading_state(
+                LoadingStateConfig::new(MyMainState::SettingUp)
+                    .load_collection::<MyTournamentConfigAsset>(),
+            )
+            .add_systems(OnEnter(MyMainState::Ready), setup_tournament)
+            .add_systems(
+                Update,
+                auto_start::start_matches_when_all_bots_joined
+                    .run_if(resource_exists::<MyTournament>),
+            )
+            .add_observer(handle_results::handle_tournament_match_finished)
+            .add_observer(standings_request::handle_tournament_standings_request);
+    }
+}
+
+fn setup_tournament(
+    mut commands: Commands,
+    tournament_config: TournamentConfigSystemParam,
+    map_config: MapConfigSystemParam,
+) {
+    let tournament_config = tournament_config.tournament_config();
+    if !tournament_config.enabled {
+        return;
+    }
+
+    if tournament_config.bots.len() < 2 {
+        error!("A tournament needs at least 2 bots, not starting the tournament");
+        return;
+    }
+
+    if tournament_config.maps.is_empty() {
+        error!("A tournament needs at least one map, not starting the tournament");
+        return;
+    }
+
+    let mut team_names = HashMap::new();
+    for map_name in tournament_config.maps.iter() {
+        match map_config.get_map_config_from_name(map_name) {
+            Some(map) if map.teams.len() >= 2 => {
+                let mut teams = map.get_team_names();
+                teams.sort();
+                team_names.insert(map_name.clone(), teams);
+            }
+            Some(_) => {
+                error!(
+                    "Map \"{}\" has less than 2 teams, not starting the tournament",
+                    map_name
+                );
+                return;
+            }
+            None => {
+                error!(
+                    "Map \"{}\" not found, not starting the tournament",
+                    map_name
+                );
+                return;
+            }
+        }
+    }
+
+    let tournament = MyTournament::new(tournament_config.clone(), team_names);
+    info!(
+        "Starting tournament \"{}\" ({:?}) with {} bots and {} scheduled matches",
+        tournament_config.name,
+        tournament_config.mode,
+        tournament_config.bots.len(),
+        tournament.matches.len()
+    );
+    tournament.save_standings();
+
+    commands.insert_resource(tournament);
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use shared::networking::messages::{
+    message_container::{
+        MessageContainer, MessageTarget, NetworkMessageType, RequestTournamentStandingsTrigger,
+    },
+    message_data::message_error_types::ErrorMessageTypes,
+    message_queue::ImmediateOutMessageQueue,
+};
+
+use super::tournament::MyTournament;
+
+pub fn handle_tournament_standings_request(
+    trigger: Trigger<RequestTournamentStandingsTrigger>,
+    tournament: Option<Res<MyTournament>>,
+    mut queues: Query<&mut ImmediateOutMessageQueue>,
+) {
+    let sender = trigger.sender.expect("Failed to get sender");
+    let mut queue = queues
+        .get_mut(sender)
+        .expect("Failed to get queue for sender");
+
+    let message = match tournament {
+        Some(tournament) => NetworkMessageType::TournamentStandings(tournament.standings_data()),
+        None => NetworkMessageType::MessageError(ErrorMessageTypes::TournamentError(
+            "No tournament is running on this server".to_string(),
+        )),
+    };
+
+    queue.push_back(MessageContainer::new(
+        MessageTarget::Client(sender),
+        message,
+    ));
+}
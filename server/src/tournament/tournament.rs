@@ -0,0 +1,327 @@
+use bevy::{prelude::*, utils::HashMap};
+use shared::networking::messages::message_data::{
+    game_finished::GameFinishedData,
+    tournament::{
+        TournamentMatchInfo, TournamentMatchState, TournamentMode, TournamentStanding,
+        TournamentStandingsData,
+    },
+};
+
+use super::config::TournamentConfig;
+
+/// A lobby and the team a bot has to join for its next tournament match
+#[derive(Debug, Clone)]
+pub struct TournamentAssignment {
+    pub lobby_name: String,
+    pub map_name: String,
+    pub team_name: String,
+}
+
+#[derive(Debug, Resource)]
+pub struct MyTournament {
+    pub config: TournamentConfig,
+    /// The sorted team names of each map. The first bot of a match plays in the first team, the second bot in the second team
+    team_names: HashMap<String, Vec<String>>,
+    pub matches: Vec<TournamentMatchInfo>,
+    pub standings: HashMap<String, TournamentStanding>,
+    pub winner: Option<String>,
+    /// The bots taking part in the latest single elimination round, in seeding order
+    round_bots: Vec<String>,
+}
+
+impl MyTournament {
+    pub fn new(config: TournamentConfig, team_names: HashMap<String, Vec<String>>) -> Self {
+        let standings = config
+            .bots
+            .iter()
+            .map(|bot| (bot.clone(), TournamentStanding::new(bot.clone())))
+            .collect();
+
+        let mut tournament = MyTournament {
+            config,
+            team_names,
+            matches: Vec::new(),
+            standings,
+            winner: None,
+            round_bots: Vec::new(),
+        };
+
+        match tournament.config.mode {
+            TournamentMode::RoundRobin => tournament.schedule_round_robin(),
+            TournamentMode::SingleElimination => {
+                let bots = tournament.config.bots.clone();
+                tournament.schedule_elimination_round(0, bots);
+            }
+        }
+
+        tournament
+    }
+
+    /// Returns the lobby the bot has to join for its next match, if it has one scheduled
+    pub fn get_assignment(&self, bot_name: &str) -> Option<TournamentAssignment> {
+        self.matches
+            .iter()
+            .find(|tournament_match| {
+                tournament_match.state == TournamentMatchState::Scheduled
+                    && tournament_match.has_participant(bot_name)
+            })
+            .map(|tournament_match| TournamentAssignment {
+                lobby_name: tournament_match.lobby_name.clone(),
+                map_name: tournament_match.map_name.clone(),
+                team_name: tournament_match
+                    .team_of_participant(bot_name)
+                    .expect("Participant has no team")
+                    .clone(),
+            })
+    }
+
+    pub fn get_match_of_lobby_mut(&mut self, lobby_name: &str) -> Option<&mut TournamentMatchInfo> {
+        self.matches
+            .iter_mut()
+            .find(|tournament_match| tournament_match.lobby_name == lobby_name)
+    }
+
+    /// Stores the result of a finished match and updates the standings.
+    /// Returns false if the lobby doesn't belong to this tournament
+    pub fn record_result(&mut self, result: &GameFinishedData) -> bool {
+        let Some(tournament_match) = self.get_match_of_lobby_mut(&result.lobby_name) else {
+            return false;
+        };
+
+        if tournament_match.state == TournamentMatchState::Finished {
+            warn!(
+                "Tournament match in lobby \"{}\" already has a result, ignoring the new one",
+                result.lobby_name
+            );
+            return true;
+        }
+
+        tournament_match.state = TournamentMatchState::Finished;
+        tournament_match.score = result.score.clone();
+        tournament_match.winner = result
+            .winner
+            .as_ref()
+            .and_then(|team| tournament_match.participants.get(team).cloned());
+        let tournament_match = tournament_match.clone();
+
+        for (team, bot) in tournament_match.participants.iter() {
+            let score_for = *tournament_match.score.get(team).unwrap_or(&0);
+            let score_against = tournament_match
+                .score
+                .iter()
+                .filter(|(other_team, _)| *other_team != team)
+                .map(|(_, score)| *score)
+                .sum::<u32>();
+
+            let standing = self
+                .standings
+                .entry(bot.clone())
+                .or_insert_with(|| TournamentStanding::new(bot.clone()));
+            standing.played += 1;
+            standing.score_for += score_for;
+            standing.score_against += score_against;
+
+            match &tournament_match.winner {
+                Some(winner) if winner == bot => {
+                    standing.wins += 1;
+                    standing.points += 3;
+                }
+                Some(_) => standing.losses += 1,
+                None => {
+                    standing.draws += 1;
+                    standing.points += 1;
+                }
+            }
+        }
+
+        match self.config.mode {
+            TournamentMode::RoundRobin => {
+                if self.all_matches_finished() {
+                    self.winner = self
+                        .sorted_standings()
+                        .first()
+                        .map(|standing| standing.bot_name.clone());
+                }
+            }
+            TournamentMode::SingleElimination => {
+                self.advance_elimination_bracket(tournament_match.round)
+            }
+        }
+
+        if let Some(winner) = &self.winner {
+            info!(
+                "Tournament \"{}\" is over, winner: {}",
+                self.config.name, winner
+            );
+        }
+
+        true
+    }
+
+    pub fn all_matches_finished(&self) -> bool {
+        self.matches
+            .iter()
+            .all(|tournament_match| tournament_match.state == TournamentMatchState::Finished)
+    }
+
+    /// Best bot first, ordered by points, then wins, then score difference
+    pub fn sorted_standings(&self) -> Vec<TournamentStanding> {
+        let mut standings = self.standings.values().cloned().collect::<Vec<_>>();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.wins.cmp(&a.wins))
+                .then(
+                    (b.score_for as i64 - b.score_against as i64)
+                        .cmp(&(a.score_for as i64 - a.score_against as i64)),
+                )
+                .then(a.bot_name.cmp(&b.bot_name))
+        });
+        standings
+    }
+
+    pub fn standings_data(&self) -> TournamentStandingsData {
+        TournamentStandingsData {
+            mode: self.config.mode.clone(),
+            standings: self.sorted_standings(),
+            matches: self.matches.clone(),
+            winner: self.winner.clone(),
+        }
+    }
+
+    /// Writes the standings to the configured file, if there is one
+    pub fn save_standings(&self) {
+        if let Some(path) = &self.config.standings_file {
+            match serde_json::to_string_pretty(&self.standings_data()) {
+                Ok(json) => {
+                    if let Err(err) = std::fs::write(path, json) {
+                        error!("Failed to write tournament standings to {}: {}", path, err);
+                    }
+                }
+                Err(err) => error!("Failed to serialize tournament standings: {}", err),
+            }
+        }
+    }
+
+    /// Every bot plays against every other bot once on each map.
+    /// Uses the circle method, so the matches of one round never share a bot and can run at the same time
+    fn schedule_round_robin(&mut self) {
+        let bots = self.config.bots.clone();
+        let mut slots: Vec<Option<usize>> = (0..bots.len()).map(Some).collect();
+        if slots.len() % 2 == 1 {
+            // Whoever gets paired with the empty slot sits this round out
+            slots.push(None);
+        }
+        let slot_count = slots.len();
+
+        let mut rounds = Vec::new();
+        for _ in 0..slot_count.saturating_sub(1) {
+            let mut pairings = Vec::new();
+            for i in 0..slot_count / 2 {
+                if let (Some(first), Some(second)) = (slots[i], slots[slot_count - 1 - i]) {
+                    pairings.push((first, second));
+                }
+            }
+            rounds.push(pairings);
+
+            // The first slot stays fixed, all others rotate
+            slots[1..].rotate_right(1);
+        }
+
+        let maps = self.config.maps.clone();
+        for (map_index, map_name) in maps.iter().enumerate() {
+            for (round_index, pairings) in rounds.iter().enumerate() {
+                let round = map_index * rounds.len() + round_index;
+                for (first, second) in pairings.iter() {
+                    self.add_match(round, map_name, &bots[*first], &bots[*second]);
+                }
+            }
+        }
+    }
+
+    /// Pairs up the bots in order. If the number of bots is odd, the last one advances without playing
+    fn schedule_elimination_round(&mut self, round: usize, bots: Vec<String>) {
+        self.round_bots = bots.clone();
+        if bots.len() == 1 {
+            self.winner = Some(bots[0].clone());
+            return;
+        }
+
+        let map_name = self.config.maps[round % self.config.maps.len()].clone();
+        for pairing in bots.chunks(2) {
+            if let [first, second] = pairing {
+                self.add_match(round, &map_name, first, second);
+            }
+        }
+    }
+
+    /// Once all matches of the round are done, schedules the next round with all winners.
+    /// A draw counts as a win for the higher seeded bot (the one playing in the first team)
+    fn advance_elimination_bracket(&mut self, round: usize) {
+        let round_matches = self
+            .matches
+            .iter()
+            .filter(|tournament_match| tournament_match.round == round)
+            .collect::<Vec<_>>();
+
+        if round_matches
+            .iter()
+            .any(|tournament_match| tournament_match.state != TournamentMatchState::Finished)
+        {
+            return;
+        }
+
+        let (advancing, eliminated): (Vec<String>, Vec<String>) =
+            self.round_bots.iter().cloned().partition(|bot| {
+                !round_matches.iter().any(|tournament_match| {
+                    tournament_match.has_participant(bot)
+                        && match &tournament_match.winner {
+                            Some(winner) => winner != bot,
+                            None => self.first_participant(tournament_match) != Some(bot),
+                        }
+                })
+            });
+
+        for bot in eliminated.iter() {
+            if let Some(standing) = self.standings.get_mut(bot) {
+                standing.eliminated = true;
+            }
+        }
+
+        self.schedule_elimination_round(round + 1, advancing);
+    }
+
+    /// The bot playing in the first team of the match
+    fn first_participant<'a>(
+        &self,
+        tournament_match: &'a TournamentMatchInfo,
+    ) -> Option<&'a String> {
+        self.team_names
+            .get(&tournament_match.map_name)
+            .and_then(|teams| teams.first())
+            .and_then(|team| tournament_match.participants.get(team))
+    }
+
+    fn add_match(&mut self, round: usize, map_name: &str, first: &str, second: &str) {
+        let match_id = self.matches.len();
+        let teams = self
+            .team_names
+            .get(map_name)
+            .expect("Map of tournament match has no teams");
+
+        let mut participants = HashMap::new();
+        participants.insert(teams[0].clone(), first.to_string());
+        participants.insert(teams[1].clone(), second.to_string());
+
+        self.matches.push(TournamentMatchInfo {
+            match_id,
+            round,
+            lobby_name: format!("{}-match-{}", self.config.name, match_id),
+            map_name: map_name.to_string(),
+            participants,
+            state: TournamentMatchState::Scheduled,
+            score: HashMap::new(),
+            winner: None,
+        });
+    }
+}
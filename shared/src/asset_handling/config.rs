@@ -7,11 +7,13 @@ use bevy_asset_loader::{
     },
 };
 use bevy_common_assets::ron::RonAssetPlugin;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    game::{collision_handling::structs::Side, tank_types::TankType},
+    game::{collision_handling::structs::Side, flag::FlagRules, tank_types::TankType},
     main_state::MyMainState,
+    networking::lobby_management::GameMode,
 };
 
 pub struct MyConfigPlugin;
@@ -25,6 +27,11 @@ impl Plugin for MyConfigPlugin {
         ))
         .register_type::<MyConfigAsset>()
         .register_type::<ServerConfig>()
+        .register_type::<MatchmakingConfig>()
+        .register_type::<TeamBalancing>()
+        .register_type::<RatingConfig>()
+        .register_type::<AuthenticationConfig>()
+        .register_type::<TrafficLimitsConfig>()
         .register_type::<ClientConfig>()
         .register_type::<TankConfigs>()
         .register_type::<TankConfig>()
@@ -51,6 +58,103 @@ pub struct ServerConfig {
     pub port: u16,
     pub tick_rate: u64,
     pub timeout_first_contact: u64, // in milliseconds
+    /// How many ticks ahead a client can schedule a message with its tick target
+    #[serde(default = "default_max_ticks_ahead")]
+    pub max_ticks_ahead: u64,
+    /// If None, players have to name the lobby they want to join
+    pub matchmaking: Option<MatchmakingConfig>,
+    /// If None, the server doesn't keep ratings
+    pub rating: Option<RatingConfig>,
+    /// If None, every client can connect under any bot name.
+    /// Clients that fail to authenticate get disconnected
+    pub authentication: Option<AuthenticationConfig>,
+    /// Clients connecting as admin have to send this secret. If None, admin clients are disabled
+    pub admin_secret: Option<String>,
+    #[serde(default)]
+    pub traffic_limits: TrafficLimitsConfig,
+}
+
+fn default_max_ticks_ahead() -> u64 {
+    20
+}
+
+#[derive(Debug, Reflect, Clone, Deserialize)]
+pub struct TrafficLimitsConfig {
+    /// The biggest message frame a client can send, in bytes. Bigger frames get the client disconnected right away
+    pub max_frame_size: usize,
+    /// How many messages a client can send per tick, the rest gets dropped
+    pub max_messages_per_tick: usize,
+    /// How many messages of a type a client can send per second, e.g. {"SimpleTextMessage": 5}
+    /// Message types that aren't listed aren't limited
+    pub message_type_rates: HashMap<String, u32>,
+    /// Every frame breaking a limit is a strike. With this many strikes, the client gets muted
+    pub mute_after_strikes: u32,
+    /// How long a muted client gets ignored, in milliseconds
+    pub mute_duration: u64,
+    /// With this many strikes, the client gets disconnected
+    pub disconnect_after_strikes: u32,
+    /// The strikes get reset if the client didn't break a limit for this long, in milliseconds
+    pub strikes_reset_after: u64,
+}
+
+impl Default for TrafficLimitsConfig {
+    fn default() -> Self {
+        TrafficLimitsConfig {
+            max_frame_size: 65536,
+            max_messages_per_tick: 32,
+            message_type_rates: HashMap::new(),
+            mute_after_strikes: 3,
+            mute_duration: 5000,
+            disconnect_after_strikes: 5,
+            strikes_reset_after: 30000,
+        }
+    }
+}
+
+#[derive(Debug, Default, Reflect, Clone, Deserialize)]
+pub struct AuthenticationConfig {
+    /// JSON file mapping each bot name to its token, e.g. {"my_bot": "secret"}
+    pub registry_file: String,
+    /// If true, only bots listed in the registry can join as players.
+    /// If false, unlisted bots can still join, but listed ones always need their token
+    pub require_authentication: bool,
+}
+
+#[derive(Debug, Default, Reflect, Clone, Deserialize)]
+pub struct RatingConfig {
+    /// The file the ratings get stored in (as JSON)
+    pub file: String,
+    /// The rating a bot starts with. Dummies always count with this rating
+    pub initial_rating: f32,
+    /// The maximum a rating can change with a single game
+    pub k_factor: f32,
+}
+
+#[derive(Debug, Default, Reflect, Clone, Deserialize)]
+pub struct MatchmakingConfig {
+    /// The maps matchmaking lobbies get created on, one after the other
+    pub map_rotation: Vec<String>,
+    /// How many queued players are needed to create a lobby. Capped at the number of slots of the map.
+    /// Only used if empty slots get filled with dummies, otherwise all slots have to be filled by players
+    pub min_players: usize,
+    pub fill_empty_slots_with_dummies: bool,
+    pub team_balancing: TeamBalancing,
+    /// The game ends after this many ticks. If None, the game runs until it gets stopped
+    pub max_ticks: Option<u64>,
+    #[serde(default)]
+    pub flag_rules: FlagRules,
+}
+
+#[derive(Debug, Default, Reflect, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TeamBalancing {
+    /// Players get spread over the teams in the order they were queued
+    #[default]
+    None,
+    /// Every tank type gets spread evenly over the teams
+    TankType,
+    /// The teams get a similar total rating. Needs ratings to be enabled
+    Rating,
 }
 
 #[derive(Debug, Default, Reflect, Clone, Asset, Deserialize)]
@@ -60,7 +164,23 @@ pub struct ClientConfig {
     pub map: String,
     pub name: String,
     pub lobby_name: String,
+    /// The mode of the lobby, if it doesn't exist yet and gets created by this client
+    #[serde(default)]
+    pub game_mode: GameMode,
     pub fill_empty_slots_with_dummies: bool,
+    pub max_ticks: Option<u64>,
+    pub lockstep_timeout: Option<u64>,
+    #[serde(default)]
+    pub fast_forward: bool,
+    /// Sent with the start of the game, the defaults for everything left out
+    #[serde(default)]
+    pub flag_rules: FlagRules,
+    /// If set, the client doesn't connect to a server, but shows this map from `assets/maps` and reloads it on every save
+    #[serde(default)]
+    pub preview_map: Option<String>,
+    /// Together with `preview_map`, lets the map be edited and saved. Creates the map if it doesn't exist yet
+    #[serde(default)]
+    pub edit_map: bool,
 }
 
 #[derive(Debug, Default, Reflect, Clone, Asset, Deserialize, PartialEq)]
@@ -68,7 +188,7 @@ pub struct TankConfigs {
     pub tanks: HashMap<TankType, TankConfig>,
 }
 
-#[derive(Debug, Default, Reflect, Clone, Asset, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Default, Reflect, Clone, Asset, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TankConfig {
     /// The speed at which the tank at maximum moves per tick
@@ -85,6 +205,7 @@ pub struct TankConfig {
     pub max_slope: f32,
     /// The size of the tank (Vec3, x = width, y = height, z = depth)
     /// full-extents for x (width), z (depth) and y (height)
+    #[schemars(with = "[f32; 3]")]
     pub size: Vec3,
     /// Shooting cooldown in ticks. The tank can only shoot again after this many ticks.
     pub shoot_cooldown: u32,
@@ -94,12 +215,14 @@ pub struct TankConfig {
     pub projectile_lifetime: u32,
     /// The size of the projectile (Vec3, x = width, y = height, z = depth)
     /// full-extents for x (width), z (depth) and y (height)
+    #[schemars(with = "[f32; 3]")]
     pub projectile_size: Vec3,
     pub projectile_gravity: f32,
     /// The maximum amount of health this tank can have
     pub max_health: f32,
     /// The armor of the tank on each side. value between 0 and 1
     /// 0 = no armor, 1 = full armor (no damage when no armor penetration)
+    #[schemars(with = "std::collections::HashMap<Side, f32>")]
     pub armor: HashMap<Side, f32>,
     /// The time in ticks it takes for the tank to respawn after dying
     pub respawn_timer: u32,
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use super::maps::MapDefinition;
+
+/// Below this, the ground under a body is considered flat
+const MIN_TILT: f32 = 1e-4;
+
+impl MapDefinition {
+    /// The height of the ground at a world position, interpolated bilinearly between the centers of the tiles.
+    /// Between the outermost tile centers and the border of the map the height of the border tiles is kept.
+    /// Returns None outside of the map
+    pub fn sample_height(&self, position: Vec3) -> Option<f32> {
+        if position.x < 0.0
+            || position.z < 0.0
+            || position.x >= self.width as f32
+            || position.z >= self.depth as f32
+        {
+            return None;
+        }
+
+        // Tile (x, y) has its height at its center (x + 0.5, y + 0.5)
+        let x = (position.x - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let z = (position.z - 0.5).clamp(0.0, (self.depth - 1) as f32);
+        let (x0, z0) = (x.floor() as usize, z.floor() as usize);
+        let (x1, z1) = ((x0 + 1).min(self.width - 1), (z0 + 1).min(self.depth - 1));
+        let (tx, tz) = (x - x0 as f32, z - z0 as f32);
+
+        let height = |x, z| self.get_floor_height_of_tile((x, z));
+        let near = height(x0, z0)? + (height(x1, z0)? - height(x0, z0)?) * tx;
+        let far = height(x0, z1)? + (height(x1, z1)? - height(x0, z1)?) * tx;
+
+        Some(near + (far - near) * tz)
+    }
+
+    /// The rotation of a body standing on the ground at `position`.
+    /// Keeps the heading of `rotation`, but pitches and rolls the body so it follows the ground
+    /// under the front, back and sides of its footprint (given by `half_size`)
+    pub fn get_ground_rotation(&self, position: Vec3, rotation: Quat, half_size: Vec3) -> Quat {
+        let heading = heading_of(rotation);
+        let Some(center_height) = self.sample_height(position) else {
+            return heading;
+        };
+        let height_at = |offset: Vec3| {
+            let point = position + offset;
+            point.with_y(self.sample_height(point).unwrap_or(center_height))
+        };
+
+        let forward = heading * Vec3::Z * half_size.z;
+        let side = heading * Vec3::X * half_size.x;
+        let forward = (height_at(forward) - height_at(-forward)).normalize_or_zero();
+        let side = (height_at(side) - height_at(-side)).normalize_or_zero();
+        let up = forward.cross(side).normalize_or_zero();
+        // Flat ground, or a footprint without size
+        if up == Vec3::ZERO || 1.0 - up.y < MIN_TILT {
+            return heading;
+        }
+
+        // On uneven ground the sides aren't perpendicular to the front and back, so rebuild them
+        let side = up.cross(forward);
+        Quat::from_mat3(&Mat3::from_cols(side, up, forward))
+    }
+}
+
+/// Only the rotation around the y axis, e.g. of a tank body that is tilted by the ground
+pub fn heading_of(rotation: Quat) -> Quat {
+    let forward = rotation * Vec3::Z;
+    if forward.x.abs() < f32::EPSILON && forward.z.abs() < f32::EPSILON {
+        return Quat::IDENTITY;
+    }
+
+    Quat::from_rotation_y(forward.x.atan2(forward.z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two by two tiles, rising to the east by 1 and to the south by 2
+    fn slope() -> MapDefinition {
+        MapDefinition {
+            width: 2,
+            depth: 2,
+            tiles: vec![vec![0.0, 1.0], vec![2.0, 3.0]],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn samples_the_height_of_the_tile_at_its_center() {
+        let map = slope();
+
+        assert_eq!(map.sample_height(Vec3::new(0.5, 0.0, 0.5)), Some(0.0));
+        assert_eq!(map.sample_height(Vec3::new(1.5, 0.0, 0.5)), Some(1.0));
+        assert_eq!(map.sample_height(Vec3::new(0.5, 0.0, 1.5)), Some(2.0));
+        assert_eq!(map.sample_height(Vec3::new(1.5, 0.0, 1.5)), Some(3.0));
+    }
+
+    #[test]
+    fn interpolates_between_the_tile_centers() {
+        let map = slope();
+
+        assert_eq!(map.sample_height(Vec3::new(1.0, 0.0, 0.5)), Some(0.5));
+        assert_eq!(map.sample_height(Vec3::new(0.5, 0.0, 1.0)), Some(1.0));
+        assert_eq!(map.sample_height(Vec3::new(1.0, 0.0, 1.0)), Some(1.5));
+    }
+
+    #[test]
+    fn keeps_the_height_of_the_border_tiles_up_to_the_border() {
+        let map = slope();
+
+        assert_eq!(map.sample_height(Vec3::new(0.0, 0.0, 0.0)), Some(0.0));
+        assert_eq!(map.sample_height(Vec3::new(1.99, 0.0, 1.99)), Some(3.0));
+        assert_eq!(map.sample_height(Vec3::new(1.0, 0.0, 0.1)), Some(0.5));
+    }
+
+    #[test]
+    fn samples_no_height_outside_the_map() {
+        let map = slope();
+
+        assert_eq!(map.sample_height(Vec3::new(-0.1, 0.0, 0.5)), None);
+        assert_eq!(map.sample_height(Vec3::new(0.5, 0.0, -0.1)), None);
+        assert_eq!(map.sample_height(Vec3::new(2.0, 0.0, 0.5)), None);
+        assert_eq!(map.sample_height(Vec3::new(0.5, 0.0, 2.0)), None);
+    }
+
+    #[test]
+    fn ignores_the_height_of_the_position() {
+        let map = slope();
+
+        assert_eq!(map.sample_height(Vec3::new(1.0, 10.0, 0.5)), Some(0.5));
+    }
+}
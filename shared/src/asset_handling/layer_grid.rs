@@ -0,0 +1,189 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use super::maps::{MapDefinition, TileDefinition};
+
+/// The distance between the points checked for walls along a line of sight, in tiles
+const LINE_OF_SIGHT_STEP: f32 = 0.25;
+
+/// The layers of every tile combined, built once from a map,
+/// so gameplay can look them up without going through every layer
+#[derive(Debug, Default, Clone, Reflect, PartialEq)]
+pub struct LayerGrid {
+    width: usize,
+    depth: usize,
+    /// Row by row, like the tiles of the map
+    tiles: Vec<TileLayers>,
+}
+
+/// What all layers of a tile do together
+#[derive(Debug, Clone, Copy, Reflect, PartialEq)]
+pub struct TileLayers {
+    pub blocks_movement: bool,
+    /// Up to which height the tile stops projectiles and blocks the line of sight, e.g. the top of a wall
+    pub blocking_top: Option<f32>,
+    pub hides_tanks: bool,
+    /// The speed modifiers of all layers of the tile multiplied
+    pub speed_modifier: f32,
+}
+
+impl Default for TileLayers {
+    fn default() -> Self {
+        TileLayers {
+            blocks_movement: false,
+            blocking_top: None,
+            hides_tanks: false,
+            speed_modifier: 1.0,
+        }
+    }
+}
+
+impl LayerGrid {
+    pub fn new(map: &MapDefinition) -> Self {
+        let mut tiles = vec![TileLayers::default(); map.width * map.depth];
+        for layer in map.layers.iter() {
+            // A tile listed twice still only gets the layer once
+            let mut layer_tiles = HashSet::new();
+            for tile in layer.tiles.iter() {
+                let Some(floor_height) = map.get_floor_height_of_tile(tile.clone()) else {
+                    continue;
+                };
+                let index = tile.y * map.width + tile.x;
+                if tile.x >= map.width || !layer_tiles.insert(index) {
+                    continue;
+                }
+                let Some(tile_layers) = tiles.get_mut(index) else {
+                    continue;
+                };
+
+                tile_layers.blocks_movement |= layer.kind.blocks_movement();
+                if let Some(blocking_height) = layer.kind.blocking_height() {
+                    let top = floor_height + blocking_height;
+                    tile_layers.blocking_top = Some(
+                        tile_layers
+                            .blocking_top
+                            .map_or(top, |blocking_top| blocking_top.max(top)),
+                    );
+                }
+                tile_layers.hides_tanks |= layer.kind.hides_tanks();
+                tile_layers.speed_modifier *= layer.kind.speed_modifier();
+            }
+        }
+
+        LayerGrid {
+            width: map.width,
+            depth: map.depth,
+            tiles,
+        }
+    }
+
+    /// None if the tile is outside the map
+    pub fn get(&self, tile: impl Into<TileDefinition>) -> Option<&TileLayers> {
+        let TileDefinition { x, y } = tile.into();
+        if x >= self.width || y >= self.depth {
+            return None;
+        }
+
+        self.tiles.get(y * self.width + x)
+    }
+
+    /// The layers of the tile a real world position is above, None if it is outside the map
+    pub fn get_below(&self, real_world_position: Vec3) -> Option<&TileLayers> {
+        let (x, z) = (real_world_position.x.floor(), real_world_position.z.floor());
+        if x < 0.0 || z < 0.0 {
+            return None;
+        }
+
+        self.get((x as usize, z as usize))
+    }
+
+    pub fn tile_blocks_movement(&self, tile: impl Into<TileDefinition>) -> bool {
+        self.get(tile)
+            .is_some_and(|tile_layers| tile_layers.blocks_movement)
+    }
+
+    /// Whether something at this height hits a layer of the tile, e.g. a wall.
+    /// Layers block from the floor of the tile up to their [`LayerType::blocking_height`](super::maps::LayerType::blocking_height)
+    pub fn tile_blocks_at_height(&self, tile: impl Into<TileDefinition>, height: f32) -> bool {
+        self.get(tile)
+            .and_then(|tile_layers| tile_layers.blocking_top)
+            .is_some_and(|blocking_top| height < blocking_top)
+    }
+
+    /// Whether no layer blocks the straight line between two positions, e.g. a wall in between
+    pub fn has_line_of_sight(&self, from: Vec3, to: Vec3) -> bool {
+        let steps = (from.distance(to) / LINE_OF_SIGHT_STEP).ceil() as usize;
+        (1..steps).all(|step| {
+            let position = from.lerp(to, step as f32 / steps as f32);
+            self.get_below(position)
+                .and_then(|tile_layers| tile_layers.blocking_top)
+                .is_none_or(|blocking_top| position.y >= blocking_top)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asset_handling::maps::{LayerDefinition, LayerType, WALL_HEIGHT};
+
+    use super::*;
+
+    fn layer(kind: LayerType, tiles: &[(usize, usize)]) -> LayerDefinition {
+        LayerDefinition {
+            kind,
+            cost_modifier: 1.0,
+            tiles: tiles.iter().map(|tile| (*tile).into()).collect(),
+        }
+    }
+
+    /// Three by three tiles, with the middle column one higher
+    fn map(layers: Vec<LayerDefinition>) -> MapDefinition {
+        MapDefinition {
+            width: 3,
+            depth: 3,
+            tiles: vec![vec![0.0, 1.0, 0.0]; 3],
+            layers,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn combines_the_layers_of_a_tile() {
+        let grid = LayerGrid::new(&map(vec![
+            layer(LayerType::Forest, &[(0, 0)]),
+            layer(LayerType::Mud, &[(0, 0), (0, 0)]),
+            layer(LayerType::Road, &[(0, 0), (2, 2)]),
+        ]));
+
+        assert_eq!(
+            grid.get((0, 0)),
+            Some(&TileLayers {
+                hides_tanks: true,
+                speed_modifier: 0.75,
+                ..default()
+            })
+        );
+        assert_eq!(grid.get((1, 1)), Some(&TileLayers::default()));
+        assert_eq!(grid.get((2, 2)).map(|tile| tile.speed_modifier), Some(1.5));
+        assert_eq!(grid.get((3, 0)), None);
+    }
+
+    #[test]
+    fn walls_block_up_to_their_height_above_the_floor() {
+        let grid = LayerGrid::new(&map(vec![layer(LayerType::Wall, &[(1, 1)])]));
+
+        assert!(grid.tile_blocks_movement((1, 1)));
+        assert!(grid.tile_blocks_at_height((1, 1), 1.0 + WALL_HEIGHT - 0.1));
+        assert!(!grid.tile_blocks_at_height((1, 1), 1.0 + WALL_HEIGHT));
+        assert!(!grid.tile_blocks_at_height((0, 1), 0.0));
+    }
+
+    #[test]
+    fn walls_block_the_line_of_sight() {
+        let grid = LayerGrid::new(&map(vec![layer(LayerType::Wall, &[(1, 1)])]));
+        let (from, to) = (Vec3::new(0.5, 1.0, 1.5), Vec3::new(2.5, 1.0, 1.5));
+
+        assert!(!grid.has_line_of_sight(from, to));
+        assert!(grid.has_line_of_sight(from + Vec3::Y * WALL_HEIGHT, to + Vec3::Y * WALL_HEIGHT));
+        assert!(grid.has_line_of_sight(from.with_z(0.5), to.with_z(0.5)));
+    }
+}
@@ -0,0 +1,539 @@
+use std::fmt;
+
+use bevy::utils::HashMap;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    config::TankConfigs,
+    map_validation::{MapValidationError, UnreachableFlag},
+    maps::{
+        LayerDefinition, LayerType, LookDirection, MapConfig, MapDefinition, MarkerDefinition,
+        MarkerType, SimplifiedRGB, TeamConfig, TileDefinition,
+    },
+};
+
+/// How often the generator starts over when a tank can't reach an enemy flag
+const MAX_ATTEMPTS: usize = 20;
+/// Maps smaller than this don't have room for the bases of the teams
+const MIN_MAP_SIZE: usize = 12;
+/// Over how many tiles the terrain rises again around the bases
+const BASE_FALLOFF: f32 = 5.0;
+/// Heights get rounded to multiples of 1 / HEIGHT_STEPS, so the files stay readable
+const HEIGHT_STEPS: f32 = 20.0;
+const FOREST_COST_MODIFIER: f32 = 2.0;
+
+const TEAMS: [(&str, (f32, f32, f32)); 4] = [
+    ("Red", (1.0, 0.0, 0.0)),
+    ("Blue", (0.0, 0.0, 1.0)),
+    ("Green", (0.0, 1.0, 0.0)),
+    ("Yellow", (1.0, 1.0, 0.0)),
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MapSymmetry {
+    /// The half (or quarter) of each team is the mirror image of its neighbour
+    #[default]
+    Mirror,
+    /// The map looks the same after turning it around its center
+    Rotational,
+}
+
+/// Everything the generator needs to build a map. The same config always results in the same map
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MapGeneratorConfig {
+    pub seed: u64,
+    pub width: usize,
+    pub depth: usize,
+    /// The height of the small bumps all over the map
+    pub roughness: f32,
+    /// Gets rounded up to a multiple of the team count, so every team gets the same hills
+    pub hills: usize,
+    pub max_hill_height: f32,
+    /// The share of tiles covered by forest, from 0.0 to 1.0
+    pub forest_density: f32,
+    /// Either 2 or 4
+    pub team_count: usize,
+    pub players_per_team: usize,
+    /// Four teams with rotational symmetry need a square map
+    pub symmetry: MapSymmetry,
+}
+
+impl Default for MapGeneratorConfig {
+    fn default() -> Self {
+        MapGeneratorConfig {
+            seed: 0,
+            width: 40,
+            depth: 60,
+            roughness: 0.3,
+            hills: 6,
+            max_hill_height: 2.0,
+            forest_density: 0.1,
+            team_count: 2,
+            players_per_team: 4,
+            symmetry: MapSymmetry::Mirror,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapGenerationError {
+    UnsupportedTeamCount(usize),
+    /// Turning the map between four teams only works if it is square
+    NotSquare {
+        width: usize,
+        depth: usize,
+    },
+    TooSmall {
+        width: usize,
+        depth: usize,
+    },
+    /// The spawns of a team don't fit around its flag base
+    NoRoomForSpawns {
+        players_per_team: usize,
+    },
+    /// Even the last attempt had spawns from which a tank couldn't reach an enemy flag
+    Unreachable(Vec<UnreachableFlag>),
+    /// The generated map didn't pass the validation, which is a bug in the generator
+    Invalid(Vec<MapValidationError>),
+}
+
+impl fmt::Display for MapGenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapGenerationError::UnsupportedTeamCount(team_count) => write!(
+                f,
+                "maps can be generated for 2 or 4 teams, not {}",
+                team_count
+            ),
+            MapGenerationError::NotSquare { width, depth } => write!(
+                f,
+                "rotational symmetry for 4 teams needs a square map, but it is {}x{}",
+                width, depth
+            ),
+            MapGenerationError::TooSmall { width, depth } => write!(
+                f,
+                "the map is {}x{}, but has to be at least {}x{}",
+                width, depth, MIN_MAP_SIZE, MIN_MAP_SIZE
+            ),
+            MapGenerationError::NoRoomForSpawns { players_per_team } => write!(
+                f,
+                "there is no room for {} spawns per team",
+                players_per_team
+            ),
+            MapGenerationError::Unreachable(unreachable) => write!(
+                f,
+                "no map with reachable flags found after {} attempts:\n\t{}",
+                MAX_ATTEMPTS,
+                unreachable
+                    .iter()
+                    .map(|unreachable| unreachable.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n\t")
+            ),
+            MapGenerationError::Invalid(errors) => write!(
+                f,
+                "the generated map is invalid:\n\t{}",
+                errors
+                    .iter()
+                    .map(|error| error.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n\t")
+            ),
+        }
+    }
+}
+
+impl MapGeneratorConfig {
+    /// Builds a map in which every tank type can drive from each spawn to every enemy flag
+    pub fn generate(&self, tank_configs: &TankConfigs) -> Result<MapConfig, MapGenerationError> {
+        self.check_parameters()?;
+
+        let flag_base = self.flag_base_tile();
+        let spawns = self.spawn_tiles(flag_base)?;
+        let markers = self.markers(flag_base, &spawns)?;
+
+        // Keeps the area around the bases flat and free of forest
+        let flag_bases = self.tile_images(flag_base);
+        let clear_radius = spawns
+            .iter()
+            .map(|spawn| tile_distance(*spawn, flag_base))
+            .fold(0.0, f32::max)
+            + 1.5;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut unreachable = Vec::new();
+        for _ in 0..MAX_ATTEMPTS {
+            let tiles = self.generate_heights(&mut rng, &flag_bases, clear_radius);
+            let forest = self.generate_forest(&mut rng, &flag_bases, clear_radius);
+
+            let map_config = MapConfig {
+                teams: self.teams(),
+                map: MapDefinition {
+                    width: self.width,
+                    depth: self.depth,
+                    floor_color: (0.5, 0.5, 0.5).into(),
+                    tiles,
+                    layers: if forest.is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![LayerDefinition {
+                            kind: LayerType::Forest,
+                            cost_modifier: FOREST_COST_MODIFIER,
+                            tiles: forest,
+                        }]
+                    },
+                    markers: markers.clone(),
+                },
+            };
+            map_config.validate().map_err(MapGenerationError::Invalid)?;
+
+            unreachable = map_config.find_unreachable_flags(tank_configs);
+            if unreachable.is_empty() {
+                return Ok(map_config);
+            }
+        }
+
+        Err(MapGenerationError::Unreachable(unreachable))
+    }
+
+    fn check_parameters(&self) -> Result<(), MapGenerationError> {
+        if self.team_count != 2 && self.team_count != 4 {
+            return Err(MapGenerationError::UnsupportedTeamCount(self.team_count));
+        }
+        if self.width < MIN_MAP_SIZE || self.depth < MIN_MAP_SIZE {
+            return Err(MapGenerationError::TooSmall {
+                width: self.width,
+                depth: self.depth,
+            });
+        }
+        if self.team_count == 4
+            && self.symmetry == MapSymmetry::Rotational
+            && self.width != self.depth
+        {
+            return Err(MapGenerationError::NotSquare {
+                width: self.width,
+                depth: self.depth,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The tile that corresponds to `(x, y)` in the part of each team, starting with the first team
+    fn tile_images(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
+        let (w, d) = (self.width - 1, self.depth - 1);
+        match (self.team_count, self.symmetry) {
+            (2, MapSymmetry::Mirror) => vec![(x, y), (x, d - y)],
+            (2, MapSymmetry::Rotational) => vec![(x, y), (w - x, d - y)],
+            (4, MapSymmetry::Mirror) => vec![(x, y), (w - x, y), (w - x, d - y), (x, d - y)],
+            (4, MapSymmetry::Rotational) => vec![(x, y), (w - y, x), (w - x, d - y), (y, d - x)],
+            _ => vec![(x, y)],
+        }
+    }
+
+    /// Same as [`Self::tile_images`], but for a position anywhere on the map
+    fn point_images(&self, (x, y): (f32, f32)) -> Vec<(f32, f32)> {
+        let (w, d) = (self.width as f32, self.depth as f32);
+        match (self.team_count, self.symmetry) {
+            (2, MapSymmetry::Mirror) => vec![(x, y), (x, d - y)],
+            (2, MapSymmetry::Rotational) => vec![(x, y), (w - x, d - y)],
+            (4, MapSymmetry::Mirror) => vec![(x, y), (w - x, y), (w - x, d - y), (x, d - y)],
+            (4, MapSymmetry::Rotational) => vec![(x, y), (w - y, x), (w - x, d - y), (y, d - x)],
+            _ => vec![(x, y)],
+        }
+    }
+
+    /// The flag base of the first team, the others are its images
+    fn flag_base_tile(&self) -> (usize, usize) {
+        let margin_x = (self.width / 8).max(2);
+        let margin_y = (self.depth / 8).max(2);
+        match self.team_count {
+            2 => (self.width / 2, margin_y),
+            _ => (margin_x, margin_y),
+        }
+    }
+
+    /// The spawns of the first team, in an arc around its flag base facing the center of the map
+    fn spawn_tiles(
+        &self,
+        flag_base: (usize, usize),
+    ) -> Result<Vec<(usize, usize)>, MapGenerationError> {
+        let no_room = MapGenerationError::NoRoomForSpawns {
+            players_per_team: self.players_per_team,
+        };
+        let count = self.players_per_team;
+        let (base_x, base_y) = (flag_base.0 as f32 + 0.5, flag_base.1 as f32 + 0.5);
+        let towards_center =
+            (self.depth as f32 / 2.0 - base_y).atan2(self.width as f32 / 2.0 - base_x);
+        let step = (std::f32::consts::PI / count.max(1) as f32).min(0.5);
+        let radius = (1.5 / step).max(3.0);
+
+        let mut spawns = Vec::new();
+        for spawn_number in 0..count {
+            let angle = towards_center + (spawn_number as f32 - (count - 1) as f32 / 2.0) * step;
+            let x = (base_x + radius * angle.cos()).floor();
+            let y = (base_y + radius * angle.sin()).floor();
+            if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.depth as f32 {
+                return Err(no_room);
+            }
+
+            let tile = (x as usize, y as usize);
+            if tile == flag_base || spawns.contains(&tile) {
+                return Err(no_room);
+            }
+            spawns.push(tile);
+        }
+
+        Ok(spawns)
+    }
+
+    fn markers(
+        &self,
+        flag_base: (usize, usize),
+        spawns: &[(usize, usize)],
+    ) -> Result<Vec<MarkerDefinition>, MapGenerationError> {
+        let mut markers = Vec::new();
+        for (team_index, (team_name, _)) in TEAMS.iter().take(self.team_count).enumerate() {
+            let (x, y) = self.tile_images(flag_base)[team_index];
+            markers.push(MarkerDefinition {
+                tile: (x, y).into(),
+                group: team_name.to_string(),
+                kind: MarkerType::FlagBase { flag_number: 0 },
+            });
+
+            for (spawn_number, spawn) in spawns.iter().enumerate() {
+                let tile = self.tile_images(*spawn)[team_index];
+                markers.push(MarkerDefinition {
+                    tile: tile.into(),
+                    group: team_name.to_string(),
+                    kind: MarkerType::Spawn {
+                        spawn_number,
+                        look_direction: self.look_direction_to_center(tile),
+                    },
+                });
+            }
+        }
+
+        // Teams close to each other could end up with spawns on the same tile
+        for (index, marker) in markers.iter().enumerate() {
+            if markers[..index]
+                .iter()
+                .any(|other| other.tile == marker.tile)
+            {
+                return Err(MapGenerationError::NoRoomForSpawns {
+                    players_per_team: self.players_per_team,
+                });
+            }
+        }
+
+        Ok(markers)
+    }
+
+    fn look_direction_to_center(&self, (x, y): (usize, usize)) -> LookDirection {
+        let dx = self.width as f32 / 2.0 - (x as f32 + 0.5);
+        let dy = self.depth as f32 / 2.0 - (y as f32 + 0.5);
+        // North is towards higher y, west towards higher x (see `MapDefinition::get_neighbours`)
+        if dy.abs() >= dx.abs() {
+            if dy >= 0.0 {
+                LookDirection::North
+            } else {
+                LookDirection::South
+            }
+        } else if dx >= 0.0 {
+            LookDirection::West
+        } else {
+            LookDirection::East
+        }
+    }
+
+    fn teams(&self) -> HashMap<String, TeamConfig> {
+        TEAMS
+            .iter()
+            .take(self.team_count)
+            .map(|(team_name, color)| {
+                (
+                    team_name.to_string(),
+                    TeamConfig {
+                        team_name: team_name.to_string(),
+                        color: SimplifiedRGB::from(*color),
+                        max_players: self.players_per_team,
+                        players: Vec::new(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn generate_heights(
+        &self,
+        rng: &mut StdRng,
+        flag_bases: &[(usize, usize)],
+        clear_radius: f32,
+    ) -> Vec<Vec<f32>> {
+        let roughness = self.roughness.max(0.0);
+        let noise = self.random_grid(rng, |rng| rng.random_range(0.0..=1.0) * roughness);
+        // Blurring turns the noise into gentle waves instead of spikes
+        let mut heights = blur(&blur(&self.symmetric_average(&noise)));
+
+        let max_radius = (self.width.min(self.depth) as f32 / 4.0).max(4.0);
+        for _ in 0..self.hills.div_ceil(self.team_count) {
+            let center = (
+                rng.random_range(0.0..self.width as f32),
+                rng.random_range(0.0..self.depth as f32),
+            );
+            let height = rng.random_range(0.5..=1.0) * self.max_hill_height.max(0.0);
+            let radius = rng.random_range(3.0..=max_radius);
+
+            for (hill_x, hill_y) in self.point_images(center) {
+                for (y, row) in heights.iter_mut().enumerate() {
+                    for (x, tile_height) in row.iter_mut().enumerate() {
+                        let distance = ((x as f32 + 0.5 - hill_x).powi(2)
+                            + (y as f32 + 0.5 - hill_y).powi(2))
+                        .sqrt();
+                        if distance < radius {
+                            *tile_height += height * (1.0 - (distance / radius).powi(2)).powi(2);
+                        }
+                    }
+                }
+            }
+        }
+        heights = blur(&heights);
+
+        for (y, row) in heights.iter_mut().enumerate() {
+            for (x, tile_height) in row.iter_mut().enumerate() {
+                let distance = flag_bases
+                    .iter()
+                    .map(|flag_base| tile_distance((x, y), *flag_base))
+                    .fold(f32::MAX, f32::min);
+                let t = ((distance - clear_radius) / BASE_FALLOFF).clamp(0.0, 1.0);
+                *tile_height *= t * t * (3.0 - 2.0 * t);
+                *tile_height = (*tile_height * HEIGHT_STEPS).round() / HEIGHT_STEPS;
+            }
+        }
+
+        self.make_symmetric(&heights)
+    }
+
+    /// Forest grows in clusters and keeps away from the bases
+    fn generate_forest(
+        &self,
+        rng: &mut StdRng,
+        flag_bases: &[(usize, usize)],
+        clear_radius: f32,
+    ) -> Vec<TileDefinition> {
+        let noise = self.random_grid(rng, |rng| rng.random_range(0.0..=1.0));
+        let mut values = self.make_symmetric(&blur(&blur(&self.symmetric_average(&noise))));
+        for (y, row) in values.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                if flag_bases
+                    .iter()
+                    .any(|flag_base| tile_distance((x, y), *flag_base) <= clear_radius)
+                {
+                    *value = f32::NEG_INFINITY;
+                }
+            }
+        }
+
+        let forest_tiles = ((self.width * self.depth) as f32 * self.forest_density.clamp(0.0, 1.0))
+            .round() as usize;
+        if forest_tiles == 0 {
+            return Vec::new();
+        }
+
+        let mut sorted = values.iter().flatten().copied().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+        // Symmetric tiles have the same value, so either all or none of them become forest
+        let threshold = sorted[forest_tiles.min(sorted.len()) - 1];
+
+        let mut forest = Vec::new();
+        for (y, row) in values.iter().enumerate() {
+            for (x, value) in row.iter().enumerate() {
+                if value.is_finite() && *value >= threshold {
+                    forest.push((x, y).into());
+                }
+            }
+        }
+        forest
+    }
+
+    fn random_grid(
+        &self,
+        rng: &mut StdRng,
+        mut value: impl FnMut(&mut StdRng) -> f32,
+    ) -> Vec<Vec<f32>> {
+        (0..self.depth)
+            .map(|_| (0..self.width).map(|_| value(rng)).collect())
+            .collect()
+    }
+
+    /// Each tile gets the average of all its images, which keeps the grid continuous
+    fn symmetric_average(&self, grid: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        (0..self.depth)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let images = self.tile_images((x, y));
+                        images.iter().map(|(x, y)| grid[*y][*x]).sum::<f32>() / images.len() as f32
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Copies the value of one image to all others, so rounding errors can't break the symmetry
+    fn make_symmetric(&self, grid: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        (0..self.depth)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let (x, y) = self
+                            .tile_images((x, y))
+                            .into_iter()
+                            .min_by_key(|(x, y)| (*y, *x))
+                            .unwrap_or((x, y));
+                        grid[y][x]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl MapConfig {
+    /// The map in the format of the `.map.ron` files in `assets/maps`
+    pub fn to_ron_string(&self) -> Result<String, ron::Error> {
+        // Deeper than that, e.g. rows of tiles and markers, goes on a single line
+        ron::ser::to_string_pretty(self, PrettyConfig::new().depth_limit(3))
+    }
+}
+
+fn tile_distance(a: (usize, usize), b: (usize, usize)) -> f32 {
+    (a.0 as f32 - b.0 as f32).hypot(a.1 as f32 - b.1 as f32)
+}
+
+/// Averages every tile with its neighbours
+fn blur(grid: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let depth = grid.len();
+    (0..depth)
+        .map(|y| {
+            let width = grid[y].len();
+            (0..width)
+                .map(|x| {
+                    let mut sum = 0.0;
+                    let mut count = 0;
+                    for row in &grid[y.saturating_sub(1)..(y + 2).min(depth)] {
+                        for height in &row[x.saturating_sub(1)..(x + 2).min(width)] {
+                            sum += height;
+                            count += 1;
+                        }
+                    }
+                    sum / count as f32
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,559 @@
+use std::{collections::VecDeque, fmt};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::game::tank_types::TankType;
+
+use super::{
+    config::TankConfigs,
+    maps::{AllMapsAsset, MapConfig, MapDefinition, MarkerType, TileDefinition},
+};
+
+/// A mistake in a map file, found by [`MapConfig::validate`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapValidationError {
+    /// `tiles` needs one row per `depth`
+    WrongRowCount {
+        expected: usize,
+        actual: usize,
+    },
+    /// Every row of `tiles` needs `width` values
+    WrongRowLength {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    LayerTileOutsideGrid {
+        layer: usize,
+        tile: TileDefinition,
+    },
+    MarkerOutsideGrid {
+        marker: usize,
+        tile: TileDefinition,
+    },
+    /// Spawns and flag bases can't share their tile with an obstacle
+    MarkerOnObstacle {
+        marker: usize,
+        tile: TileDefinition,
+    },
+    /// The group of a marker has to be the name of a team
+    UnknownMarkerGroup {
+        marker: usize,
+        group: String,
+    },
+    DuplicateSpawnNumber {
+        team: String,
+        spawn_number: usize,
+    },
+    /// Every player of a team needs its own spawn
+    NotEnoughSpawns {
+        team: String,
+        spawns: usize,
+        max_players: usize,
+    },
+    MissingFlagBase {
+        team: String,
+    },
+    /// The key of a team has to match its `teamName`
+    TeamNameMismatch {
+        key: String,
+        team_name: String,
+    },
+}
+
+impl fmt::Display for MapValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapValidationError::WrongRowCount { expected, actual } => write!(
+                f,
+                "tiles has {} rows, but the depth of the map is {}",
+                actual, expected
+            ),
+            MapValidationError::WrongRowLength {
+                row,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row {} of tiles has {} values, but the width of the map is {}",
+                row, actual, expected
+            ),
+            MapValidationError::LayerTileOutsideGrid { layer, tile } => write!(
+                f,
+                "layer {} contains tile (x: {}, y: {}), which is outside the map",
+                layer, tile.x, tile.y
+            ),
+            MapValidationError::MarkerOutsideGrid { marker, tile } => write!(
+                f,
+                "marker {} is on tile (x: {}, y: {}), which is outside the map",
+                marker, tile.x, tile.y
+            ),
+            MapValidationError::MarkerOnObstacle { marker, tile } => write!(
+                f,
+                "marker {} is on tile (x: {}, y: {}), which is blocked by an obstacle",
+                marker, tile.x, tile.y
+            ),
+            MapValidationError::UnknownMarkerGroup { marker, group } => write!(
+                f,
+                "marker {} belongs to group \"{}\", which isn't a team",
+                marker, group
+            ),
+            MapValidationError::DuplicateSpawnNumber { team, spawn_number } => write!(
+                f,
+                "team \"{}\" has more than one spawn with spawnNumber {}",
+                team, spawn_number
+            ),
+            MapValidationError::NotEnoughSpawns {
+                team,
+                spawns,
+                max_players,
+            } => write!(
+                f,
+                "team \"{}\" has {} spawns, but up to {} players",
+                team, spawns, max_players
+            ),
+            MapValidationError::MissingFlagBase { team } => {
+                write!(f, "team \"{}\" has no FlagBase marker", team)
+            }
+            MapValidationError::TeamNameMismatch { key, team_name } => {
+                write!(f, "team \"{}\" has the teamName \"{}\"", key, team_name)
+            }
+        }
+    }
+}
+
+/// A spawn from which a tank type can't drive to a flag base of another team
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnreachableFlag {
+    pub team: String,
+    pub spawn_number: usize,
+    pub enemy_team: String,
+    pub tank_type: TankType,
+}
+
+impl fmt::Display for UnreachableFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a {:?} can't drive from spawn {} of team \"{}\" to the flag base of team \"{}\"",
+            self.tank_type, self.spawn_number, self.team, self.enemy_team
+        )
+    }
+}
+
+impl MapDefinition {
+    /// Checks the grid and that every layer and marker is on it
+    pub fn validate(&self) -> Vec<MapValidationError> {
+        let mut errors = Vec::new();
+
+        if self.tiles.len() != self.depth {
+            errors.push(MapValidationError::WrongRowCount {
+                expected: self.depth,
+                actual: self.tiles.len(),
+            });
+        }
+        for (row, heights) in self.tiles.iter().enumerate() {
+            if heights.len() != self.width {
+                errors.push(MapValidationError::WrongRowLength {
+                    row,
+                    expected: self.width,
+                    actual: heights.len(),
+                });
+            }
+        }
+
+        for (layer_index, layer) in self.layers.iter().enumerate() {
+            for tile in layer.tiles.iter() {
+                if !self.is_on_grid(tile) {
+                    errors.push(MapValidationError::LayerTileOutsideGrid {
+                        layer: layer_index,
+                        tile: tile.clone(),
+                    });
+                }
+            }
+        }
+
+        for (marker_index, marker) in self.markers.iter().enumerate() {
+            if !self.is_on_grid(&marker.tile) {
+                errors.push(MapValidationError::MarkerOutsideGrid {
+                    marker: marker_index,
+                    tile: marker.tile.clone(),
+                });
+            }
+        }
+
+        let obstacle_tiles = self
+            .markers
+            .iter()
+            .filter(|marker| matches!(marker.kind, MarkerType::Obstacle { .. }))
+            .map(|marker| &marker.tile)
+            .collect::<Vec<_>>();
+        for (marker_index, marker) in self.markers.iter().enumerate() {
+            if !matches!(marker.kind, MarkerType::Obstacle { .. })
+                && obstacle_tiles.contains(&&marker.tile)
+            {
+                errors.push(MapValidationError::MarkerOnObstacle {
+                    marker: marker_index,
+                    tile: marker.tile.clone(),
+                });
+            }
+        }
+
+        errors
+    }
+
+    fn is_on_grid(&self, tile: &TileDefinition) -> bool {
+        tile.x < self.width && tile.y < self.depth
+    }
+
+    /// All tiles a tank can drive to from `start`, indexed by `y * width + x`.
+    /// A tank can move to a neighbouring tile if the height difference is at most `max_slope`
+    /// and no layer of it blocks movement. Obstacles are ignored, as they can be shot down
+    pub fn reachable_tiles(&self, start: &TileDefinition, max_slope: f32) -> Vec<bool> {
+        let mut reachable = vec![false; self.width * self.depth];
+        if !self.is_on_grid(start) {
+            return reachable;
+        }
+
+        let blocked = self.movement_blocked_tiles();
+        if blocked[start.y * self.width + start.x] {
+            return reachable;
+        }
+
+        reachable[start.y * self.width + start.x] = true;
+        let mut queue = VecDeque::from([(start.x, start.y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbours = self.get_neighbours((x, y));
+            for neighbour in [
+                neighbours.north,
+                neighbours.east,
+                neighbours.south,
+                neighbours.west,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                let index = neighbour.y * self.width + neighbour.x;
+                if !reachable[index]
+                    && !blocked[index]
+                    && self.can_climb_between((x, y), neighbour.clone(), max_slope)
+                {
+                    reachable[index] = true;
+                    queue.push_back((neighbour.x, neighbour.y));
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+impl MapConfig {
+    /// Checks the map and that every team has its markers
+    pub fn validate(&self) -> Result<(), Vec<MapValidationError>> {
+        let mut errors = self.map.validate();
+
+        for (marker_index, marker) in self.map.markers.iter().enumerate() {
+            if matches!(marker.kind, MarkerType::Obstacle { .. }) {
+                continue;
+            }
+            if !self.teams.contains_key(&marker.group) {
+                errors.push(MapValidationError::UnknownMarkerGroup {
+                    marker: marker_index,
+                    group: marker.group.clone(),
+                });
+            }
+        }
+
+        let mut team_names = self.teams.keys().collect::<Vec<_>>();
+        team_names.sort();
+        for team_name in team_names {
+            let team = &self.teams[team_name];
+            if &team.team_name != team_name {
+                errors.push(MapValidationError::TeamNameMismatch {
+                    key: team_name.clone(),
+                    team_name: team.team_name.clone(),
+                });
+            }
+
+            let mut spawn_numbers: HashMap<usize, usize> = HashMap::new();
+            let mut flag_bases = 0;
+            for marker in self.map.markers.iter() {
+                if &marker.group != team_name {
+                    continue;
+                }
+                match &marker.kind {
+                    MarkerType::Spawn { spawn_number, .. } => {
+                        *spawn_numbers.entry(*spawn_number).or_default() += 1
+                    }
+                    MarkerType::FlagBase { .. } => flag_bases += 1,
+                    MarkerType::Obstacle { .. } => {}
+                }
+            }
+
+            let mut duplicates = spawn_numbers
+                .iter()
+                .filter(|(_, count)| **count > 1)
+                .map(|(spawn_number, _)| *spawn_number)
+                .collect::<Vec<_>>();
+            duplicates.sort();
+            for spawn_number in duplicates {
+                errors.push(MapValidationError::DuplicateSpawnNumber {
+                    team: team_name.clone(),
+                    spawn_number,
+                });
+            }
+            if spawn_numbers.len() < team.max_players {
+                errors.push(MapValidationError::NotEnoughSpawns {
+                    team: team_name.clone(),
+                    spawns: spawn_numbers.len(),
+                    max_players: team.max_players,
+                });
+            }
+            if flag_bases == 0 {
+                errors.push(MapValidationError::MissingFlagBase {
+                    team: team_name.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl MapConfig {
+    /// Checks that every tank type can drive from each spawn to the flag bases of all other teams
+    pub fn find_unreachable_flags(&self, tank_configs: &TankConfigs) -> Vec<UnreachableFlag> {
+        let mut tank_types = tank_configs.tanks.iter().collect::<Vec<_>>();
+        tank_types.sort_by_key(|(tank_type, _)| format!("{:?}", tank_type));
+
+        let mut spawns = self
+            .map
+            .markers
+            .iter()
+            .filter_map(|marker| match marker.kind {
+                MarkerType::Spawn { spawn_number, .. } => {
+                    Some((&marker.group, spawn_number, &marker.tile))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        spawns.sort_by_key(|(team, spawn_number, _)| ((*team).clone(), *spawn_number));
+
+        let mut unreachable = Vec::new();
+        for (tank_type, tank_config) in tank_types {
+            for (team, spawn_number, tile) in spawns.iter() {
+                let reachable = self.map.reachable_tiles(tile, tank_config.max_slope);
+
+                let mut enemy_teams = Vec::new();
+                for marker in self.map.markers.iter() {
+                    if &marker.group == *team
+                        || !matches!(marker.kind, MarkerType::FlagBase { .. })
+                        || !self.map.is_on_grid(&marker.tile)
+                    {
+                        continue;
+                    }
+                    if !reachable[marker.tile.y * self.map.width + marker.tile.x]
+                        && !enemy_teams.contains(&marker.group)
+                    {
+                        enemy_teams.push(marker.group.clone());
+                    }
+                }
+                enemy_teams.sort();
+
+                unreachable.extend(enemy_teams.into_iter().map(|enemy_team| UnreachableFlag {
+                    team: (*team).clone(),
+                    spawn_number: *spawn_number,
+                    enemy_team,
+                    tank_type: tank_type.clone(),
+                }));
+            }
+        }
+
+        unreachable
+    }
+}
+
+/// Removes every invalid map once they are loaded, so no lobby can be created on them
+pub fn reject_invalid_maps(
+    mut maps_asset: ResMut<AllMapsAsset>,
+    map_configs: Res<Assets<MapConfig>>,
+) {
+    maps_asset.maps.retain(|stem, handle| {
+        let Some(map_config) = map_configs.get(handle) else {
+            return true;
+        };
+
+        match map_config.validate() {
+            Ok(()) => true,
+            Err(errors) => {
+                error!(
+                    "Map \"{}\" is invalid and can't be played:\n\t{}",
+                    stem.as_ref(),
+                    errors
+                        .iter()
+                        .map(|error| error.to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n\t")
+                );
+                false
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asset_handling::maps::{
+        LayerDefinition, LayerType, LookDirection, MarkerDefinition,
+    };
+
+    use super::*;
+
+    fn test_map() -> MapConfig {
+        ron::from_str(include_str!("../../../assets/maps/test_map.map.ron")).unwrap()
+    }
+
+    fn marker(tile: (usize, usize), group: &str, kind: MarkerType) -> MarkerDefinition {
+        MarkerDefinition {
+            tile: tile.into(),
+            group: group.to_string(),
+            kind,
+        }
+    }
+
+    fn spawn(spawn_number: usize) -> MarkerType {
+        MarkerType::Spawn {
+            spawn_number,
+            look_direction: LookDirection::North,
+        }
+    }
+
+    #[test]
+    fn test_map_is_valid() {
+        assert_eq!(test_map().validate(), Ok(()));
+    }
+
+    #[test]
+    fn finds_rows_that_dont_match_the_size() {
+        let mut map_config = test_map();
+        map_config.map.tiles[3].pop();
+        map_config.map.tiles.pop();
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![
+                MapValidationError::WrongRowCount {
+                    expected: 12,
+                    actual: 11,
+                },
+                MapValidationError::WrongRowLength {
+                    row: 3,
+                    expected: 12,
+                    actual: 11,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn finds_layers_and_markers_outside_the_grid() {
+        let mut map_config = test_map();
+        map_config.map.layers.push(LayerDefinition {
+            kind: LayerType::Forest,
+            cost_modifier: 1.0,
+            tiles: vec![(0, 0).into(), (12, 0).into()],
+        });
+        map_config.map.markers[2].tile = (0, 12).into();
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![
+                MapValidationError::LayerTileOutsideGrid {
+                    layer: 0,
+                    tile: (12, 0).into(),
+                },
+                MapValidationError::MarkerOutsideGrid {
+                    marker: 2,
+                    tile: (0, 12).into(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn finds_markers_on_obstacles() {
+        let mut map_config = test_map();
+        map_config.map.markers[1].tile = (2, 7).into();
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![MapValidationError::MarkerOnObstacle {
+                marker: 1,
+                tile: (2, 7).into(),
+            }])
+        );
+    }
+
+    #[test]
+    fn finds_markers_of_unknown_teams() {
+        let mut map_config = test_map();
+        map_config.map.markers.push(marker(
+            (0, 0),
+            "Green",
+            MarkerType::FlagBase { flag_number: 0 },
+        ));
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![MapValidationError::UnknownMarkerGroup {
+                marker: 9,
+                group: "Green".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn finds_missing_spawns_and_flag_bases() {
+        let mut map_config = test_map();
+        // Red keeps two spawns, but both with number 0, and loses its flag base
+        map_config.map.markers[2].kind = spawn(0);
+        map_config.map.markers.remove(0);
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![
+                MapValidationError::DuplicateSpawnNumber {
+                    team: "Red".to_string(),
+                    spawn_number: 0,
+                },
+                MapValidationError::NotEnoughSpawns {
+                    team: "Red".to_string(),
+                    spawns: 1,
+                    max_players: 2,
+                },
+                MapValidationError::MissingFlagBase {
+                    team: "Red".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn finds_teams_named_differently_than_their_key() {
+        let mut map_config = test_map();
+        map_config.teams.get_mut("Blue").unwrap().team_name = "Green".to_string();
+
+        assert_eq!(
+            map_config.validate(),
+            Err(vec![MapValidationError::TeamNameMismatch {
+                key: "Blue".to_string(),
+                team_name: "Green".to_string(),
+            }])
+        );
+    }
+}
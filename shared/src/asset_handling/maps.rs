@@ -8,13 +8,16 @@ use bevy_asset_loader::{
     mapped::AssetFileStem,
 };
 use bevy_common_assets::ron::RonAssetPlugin;
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::{
     main_state::MyMainState,
     networking::messages::message_data::message_error_types::ErrorMessageTypes,
 };
 
+use super::{map_validation::reject_invalid_maps, pathfinding::TilePath};
+
 pub struct MyMapPlugin;
 
 impl Plugin for MyMapPlugin {
@@ -27,11 +30,14 @@ impl Plugin for MyMapPlugin {
             .register_type::<LayerType>()
             .register_type::<MarkerDefinition>()
             .register_type::<MarkerType>()
+            .register_type::<ObstacleType>()
             .register_type::<SimplifiedRGB>()
+            .register_type::<TilePath>()
             .configure_loading_state(
                 LoadingStateConfig::new(MyMainState::SettingUp).load_collection::<AllMapsAsset>(),
             )
-            .add_plugins(RonAssetPlugin::<MapConfig>::new(&["map.ron"]));
+            .add_plugins(RonAssetPlugin::<MapConfig>::new(&["map.ron"]))
+            .add_systems(OnExit(MyMainState::SettingUp), reject_invalid_maps);
     }
 }
 
@@ -41,7 +47,7 @@ pub struct AllMapsAsset {
     pub maps: HashMap<AssetFileStem, Handle<MapConfig>>,
 }
 
-#[derive(Debug, Default, Reflect, Clone, Asset, Deserialize, PartialEq)]
+#[derive(Debug, Default, Reflect, Clone, Asset, Serialize, Deserialize, PartialEq)]
 pub struct MapConfig {
     pub teams: HashMap<String, TeamConfig>,
     pub map: MapDefinition,
@@ -94,9 +100,83 @@ impl MapConfig {
     pub fn get_team_names(&self) -> Vec<String> {
         self.teams.keys().cloned().collect()
     }
+
+    /// Turns every spawn point of the map into a team of its own with room for one player.
+    /// Each of these teams gets a flag base on its spawn, the flag bases of the map are dropped
+    pub fn make_free_for_all(&mut self) {
+        let mut spawns = self
+            .map
+            .markers
+            .iter()
+            .filter_map(|marker| match &marker.kind {
+                MarkerType::Spawn { spawn_number, .. } => Some((*spawn_number, marker.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Alternate between the teams of the map, so the first players don't all start on the same side
+        spawns.sort_by(|(number_a, marker_a), (number_b, marker_b)| {
+            (number_a, &marker_a.group).cmp(&(number_b, &marker_b.group))
+        });
+
+        self.map
+            .markers
+            .retain(|marker| matches!(marker.kind, MarkerType::Obstacle { .. }));
+        self.teams.clear();
+        let player_count = spawns.len();
+        for (i, (_, mut spawn)) in spawns.into_iter().enumerate() {
+            let team_name = format!("Player {}", i + 1);
+            let hue = 360.0 * i as f32 / player_count as f32;
+            self.teams.insert(
+                team_name.clone(),
+                TeamConfig {
+                    team_name: team_name.clone(),
+                    color: Color::hsl(hue, 0.8, 0.5).into(),
+                    max_players: 1,
+                    players: Vec::new(),
+                },
+            );
+
+            if let MarkerType::Spawn { spawn_number, .. } = &mut spawn.kind {
+                *spawn_number = 0;
+            }
+            spawn.group = team_name.clone();
+            self.map.markers.push(MarkerDefinition {
+                tile: spawn.tile.clone(),
+                group: team_name,
+                kind: MarkerType::FlagBase { flag_number: 0 },
+            });
+            self.map.markers.push(spawn);
+        }
+    }
+
+    /// Removes the teams without players, together with their spawns and flag bases
+    pub fn remove_empty_teams(&mut self) {
+        self.teams.retain(|_, team| !team.players.is_empty());
+        let teams = &self.teams;
+        self.map.markers.retain(|marker| match marker.kind {
+            MarkerType::Spawn { .. } | MarkerType::FlagBase { .. } => {
+                teams.contains_key(&marker.group)
+            }
+            MarkerType::Obstacle { .. } => true,
+        });
+    }
+
+    /// The first team, by name, that still has room for a player.
+    /// Shorter names come first, so "Player 10" comes after "Player 9"
+    pub fn first_free_team(&self) -> Option<String> {
+        let mut team_names = self
+            .teams
+            .iter()
+            .filter(|(_, team)| team.players.len() < team.max_players)
+            .map(|(team_name, _)| team_name.clone())
+            .collect::<Vec<_>>();
+        team_names.sort_by_key(|team_name| (team_name.len(), team_name.clone()));
+
+        team_names.into_iter().next()
+    }
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TeamConfig {
     pub team_name: String,
@@ -107,7 +187,7 @@ pub struct TeamConfig {
     pub players: Vec<Entity>,
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SimplifiedRGB {
     pub r: f32,
@@ -138,7 +218,7 @@ impl From<SimplifiedRGB> for Color {
     }
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MapDefinition {
     pub width: usize,
@@ -310,6 +390,37 @@ impl MapDefinition {
         }
     }
 
+    /// The kinds of all layers the tile belongs to
+    pub fn get_layers_of_tile(
+        &self,
+        tile: impl Into<TileDefinition>,
+    ) -> impl Iterator<Item = &LayerType> {
+        let tile = tile.into();
+        self.layers
+            .iter()
+            .filter(move |layer| layer.tiles.contains(&tile))
+            .map(|layer| &layer.kind)
+    }
+
+    pub fn tile_blocks_movement(&self, tile: impl Into<TileDefinition>) -> bool {
+        self.get_layers_of_tile(tile)
+            .any(|layer| layer.blocks_movement())
+    }
+
+    /// The tile a real world position is above, None if it is outside the map.
+    /// Unlike [`Self::get_closest_tile`], it doesn't look for the closest tile, so it is cheap
+    pub fn get_tile_below(&self, real_world_position: Vec3) -> Option<TileDefinition> {
+        let (x, z) = (real_world_position.x.floor(), real_world_position.z.floor());
+        if x < 0.0 || z < 0.0 || x >= self.width as f32 || z >= self.depth as f32 {
+            return None;
+        }
+
+        Some(TileDefinition {
+            x: x as usize,
+            y: z as usize,
+        })
+    }
+
     pub fn get_center_of_map(&self) -> Vec3 {
         Vec3::new(self.width as f32 / 2.0, 0.0, self.depth as f32 / 2.0)
     }
@@ -336,7 +447,7 @@ impl MapDefinition {
     }
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TileDefinition {
     pub x: usize,
@@ -377,7 +488,7 @@ pub struct TileNeighbours {
     pub south_west: Option<TileDefinition>,
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct LayerDefinition {
     pub kind: LayerType,
@@ -388,24 +499,66 @@ pub struct LayerDefinition {
     pub tiles: Vec<TileDefinition>,
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+/// How high walls reach above the floor of their tiles
+pub const WALL_HEIGHT: f32 = 1.5;
+
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LayerType {
+    /// Hides tanks standing in it from enemies that aren't close
     #[default]
     Forest,
+    /// Tanks can't drive into it, projectiles fly over it
+    Water,
+    /// Blocks tanks, and projectiles and the line of sight up to [`WALL_HEIGHT`] above its floor
+    Wall,
+    /// Tanks drive slower
+    Mud,
+    /// Tanks drive faster
+    Road,
 }
 
-#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, PartialEq)]
+impl LayerType {
+    pub fn blocks_movement(&self) -> bool {
+        matches!(self, LayerType::Water | LayerType::Wall)
+    }
+
+    /// How high above the floor of its tiles the layer stops projectiles and blocks the line of sight.
+    /// None if projectiles fly through it and tanks can be seen through it
+    pub fn blocking_height(&self) -> Option<f32> {
+        match self {
+            LayerType::Wall => Some(WALL_HEIGHT),
+            _ => None,
+        }
+    }
+
+    pub fn hides_tanks(&self) -> bool {
+        matches!(self, LayerType::Forest)
+    }
+
+    /// Multiplies the move speed of tanks on tiles of this layer
+    pub fn speed_modifier(&self) -> f32 {
+        match self {
+            LayerType::Mud => 0.5,
+            LayerType::Road => 1.5,
+            _ => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct MarkerDefinition {
     pub tile: TileDefinition,
     /// The group this marker belongs to. for example a team
+    /// Obstacles don't belong to a team and can leave it out
+    #[serde(default)]
     pub group: String,
 
     pub kind: MarkerType,
 }
 
-#[derive(Debug, Clone, Reflect, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "PascalCase", tag = "type")]
 pub enum MarkerType {
     #[serde(rename_all = "camelCase")]
@@ -415,6 +568,14 @@ pub enum MarkerType {
     },
     #[serde(rename_all = "camelCase")]
     FlagBase { flag_number: usize },
+    /// A destructible object that blocks tanks and projectiles until it is shot down
+    #[serde(rename_all = "camelCase")]
+    Obstacle {
+        obstacle_type: ObstacleType,
+        /// Overrides the default health of the obstacle type
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        health: Option<f32>,
+    },
 }
 
 impl Default for MarkerType {
@@ -426,7 +587,7 @@ impl Default for MarkerType {
     }
 }
 
-#[derive(Debug, Clone, Reflect, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Reflect, Deserialize, JsonSchema, PartialEq, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LookDirection {
     #[default]
@@ -436,6 +597,18 @@ pub enum LookDirection {
     West,
 }
 
+/// Always written as a string, ron can't read a unit variant back inside the internally tagged [`MarkerType`]
+impl Serialize for LookDirection {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            LookDirection::North => "NORTH",
+            LookDirection::East => "EAST",
+            LookDirection::South => "SOUTH",
+            LookDirection::West => "WEST",
+        })
+    }
+}
+
 impl LookDirection {
     pub fn to_quat(&self) -> Quat {
         match self {
@@ -472,6 +645,15 @@ impl<'w> MapConfigSystemParam<'w> {
         self.map_configs.get(asset_id)
     }
 
+    /// The name of a map, as used by lobbies, e.g. "test_map"
+    pub fn get_map_name_from_asset_id(&self, asset_id: AssetId<MapConfig>) -> Option<String> {
+        self.maps_asset
+            .maps
+            .iter()
+            .find(|(_, handle)| handle.id() == asset_id)
+            .map(|(stem, _)| stem.as_ref().to_string().replace(".map", ""))
+    }
+
     pub fn list_map_names(&self) -> Vec<String> {
         self.maps_asset
             .maps
@@ -480,3 +662,171 @@ impl<'w> MapConfigSystemParam<'w> {
             .collect()
     }
 }
+
+#[derive(Debug, Clone, Copy, Reflect, Deserialize, JsonSchema, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ObstacleType {
+    #[default]
+    Crate,
+    House,
+    Wall,
+}
+
+impl ObstacleType {
+    pub fn default_health(&self) -> f32 {
+        match self {
+            ObstacleType::Crate => 40.0,
+            ObstacleType::House => 200.0,
+            ObstacleType::Wall => 120.0,
+        }
+    }
+
+    /// Obstacles fill at most one tile, so they never stand on two at once
+    pub fn half_size(&self) -> Vec3 {
+        match self {
+            ObstacleType::Crate => Vec3::new(0.3, 0.3, 0.3),
+            ObstacleType::House => Vec3::new(0.45, 0.6, 0.45),
+            ObstacleType::Wall => Vec3::new(0.45, 0.5, 0.45),
+        }
+    }
+}
+
+/// Written as a string for the same reason as [`LookDirection`]
+impl Serialize for ObstacleType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            ObstacleType::Crate => "CRATE",
+            ObstacleType::House => "HOUSE",
+            ObstacleType::Wall => "WALL",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_map() -> MapConfig {
+        ron::from_str(include_str!("../../../assets/maps/test_map.map.ron")).unwrap()
+    }
+
+    fn markers_of(map_config: &MapConfig, team_name: &str) -> Vec<MarkerDefinition> {
+        map_config
+            .map
+            .markers
+            .iter()
+            .filter(|marker| marker.group == team_name)
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn free_for_all_gives_every_spawn_its_own_team() {
+        let mut map_config = test_map();
+        map_config.make_free_for_all();
+
+        let mut team_names = map_config.get_team_names();
+        team_names.sort();
+        assert_eq!(
+            team_names,
+            vec!["Player 1", "Player 2", "Player 3", "Player 4"]
+        );
+        for (team_name, team) in map_config.teams.iter() {
+            assert_eq!(&team.team_name, team_name);
+            assert_eq!(team.max_players, 1);
+        }
+        assert_eq!(map_config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn free_for_all_alternates_between_the_sides_of_the_map() {
+        let mut map_config = test_map();
+        map_config.make_free_for_all();
+
+        // Sorted by spawn number, then by the team of the map
+        for (team_name, tile) in [
+            ("Player 1", (1, 9)),
+            ("Player 2", (2, 2)),
+            ("Player 3", (7, 9)),
+            ("Player 4", (11, 0)),
+        ] {
+            let markers = markers_of(&map_config, team_name);
+            assert_eq!(markers.len(), 2, "{}", team_name);
+            assert!(markers.iter().all(|marker| marker.tile == tile.into()));
+            assert!(markers
+                .iter()
+                .any(|marker| marker.kind == MarkerType::FlagBase { flag_number: 0 }));
+            assert!(markers.iter().any(|marker| matches!(
+                marker.kind,
+                MarkerType::Spawn {
+                    spawn_number: 0,
+                    ..
+                }
+            )));
+        }
+    }
+
+    #[test]
+    fn free_for_all_keeps_the_obstacles_and_drops_the_old_flag_bases() {
+        let mut map_config = test_map();
+        let obstacles = markers_of(&map_config, "");
+        map_config.make_free_for_all();
+
+        assert_eq!(markers_of(&map_config, ""), obstacles);
+        assert!(markers_of(&map_config, "Red").is_empty());
+        assert!(markers_of(&map_config, "Blue").is_empty());
+    }
+
+    #[test]
+    fn players_join_the_free_teams_in_order() {
+        let mut map_config = test_map();
+        map_config.make_free_for_all();
+
+        for (index, team_name) in ["Player 1", "Player 2", "Player 3", "Player 4"]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(map_config.first_free_team().as_deref(), Some(team_name));
+            map_config
+                .insert_player_into_team(team_name, Entity::from_raw(index as u32))
+                .unwrap();
+        }
+        assert_eq!(map_config.first_free_team(), None);
+    }
+
+    #[test]
+    fn player_10_comes_after_player_9() {
+        let mut map_config = test_map();
+        map_config.teams.clear();
+        for team_name in ["Player 10", "Player 9"] {
+            map_config.teams.insert(
+                team_name.to_string(),
+                TeamConfig {
+                    team_name: team_name.to_string(),
+                    max_players: 1,
+                    ..default()
+                },
+            );
+        }
+
+        assert_eq!(map_config.first_free_team().as_deref(), Some("Player 9"));
+    }
+
+    #[test]
+    fn removes_empty_teams_with_their_markers() {
+        let mut map_config = test_map();
+        map_config.make_free_for_all();
+        map_config
+            .insert_player_into_team("Player 2", Entity::from_raw(0))
+            .unwrap();
+
+        map_config.remove_empty_teams();
+
+        assert_eq!(map_config.get_team_names(), vec!["Player 2"]);
+        assert_eq!(markers_of(&map_config, "Player 2").len(), 2);
+        assert!(map_config.map.markers.iter().all(|marker| {
+            marker.group == "Player 2" || matches!(marker.kind, MarkerType::Obstacle { .. })
+        }));
+        assert_eq!(map_config.validate(), Ok(()));
+    }
+}
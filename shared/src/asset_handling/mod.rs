@@ -6,7 +6,12 @@ use maps::MyMapPlugin;
 use crate::main_state::MyMainState;
 
 pub mod config;
+pub mod heightfield;
+pub mod layer_grid;
+pub mod map_generator;
+pub mod map_validation;
 pub mod maps;
+pub mod pathfinding;
 
 pub struct MyAssetHandlingPlugin;
 
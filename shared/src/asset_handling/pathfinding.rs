@@ -0,0 +1,373 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::maps::{MapDefinition, TileDefinition};
+
+/// Layers can make tiles cheaper, but never free, so A* still prefers short paths
+const MIN_TILE_COST: f32 = 0.01;
+
+/// A route over the map, found by [`MapDefinition::find_path`]
+#[derive(Debug, Clone, Reflect, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TilePath {
+    /// From the start to the goal tile, both included
+    pub tiles: Vec<TileDefinition>,
+    /// The length of the path, with every tile weighted by the cost modifiers of its layers
+    pub cost: f32,
+}
+
+impl TilePath {
+    /// The center of every tile of the path, on the floor
+    pub fn world_positions(&self, map: &MapDefinition) -> Vec<Vec3> {
+        self.tiles
+            .iter()
+            .filter_map(|tile| map.get_center_of_tile(tile.clone()))
+            .collect()
+    }
+}
+
+/// An entry of the open list, the heap pops the lowest estimated cost first
+#[derive(PartialEq)]
+struct OpenTile {
+    estimated_cost: f32,
+    index: usize,
+}
+
+impl Eq for OpenTile {}
+
+impl Ord for OpenTile {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimated_cost.total_cmp(&self.estimated_cost)
+    }
+}
+
+impl PartialOrd for OpenTile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl MapDefinition {
+    /// Whether a tank that can climb `max_slope` can drive between two neighbouring tiles
+    pub fn can_drive_between(
+        &self,
+        from: impl Into<TileDefinition>,
+        to: impl Into<TileDefinition>,
+        max_slope: f32,
+    ) -> bool {
+        let (from, to) = (from.into(), to.into());
+        !self.tile_blocks_movement(from.clone())
+            && !self.tile_blocks_movement(to.clone())
+            && self.can_climb_between(from, to, max_slope)
+    }
+
+    /// Like [`Self::can_drive_between`], but only checks the heights and ignores the layers
+    pub fn can_climb_between(
+        &self,
+        from: impl Into<TileDefinition>,
+        to: impl Into<TileDefinition>,
+        max_slope: f32,
+    ) -> bool {
+        match (
+            self.get_floor_height_of_tile(from),
+            self.get_floor_height_of_tile(to),
+        ) {
+            // Heights in the map files are rounded, so allow for float errors
+            (Some(from), Some(to)) => (to - from).abs() <= max_slope + 1e-4,
+            _ => false,
+        }
+    }
+
+    /// Whether tanks can't drive onto each tile, indexed by `y * width + x`
+    pub fn movement_blocked_tiles(&self) -> Vec<bool> {
+        let mut blocked = vec![false; self.width * self.depth];
+        for layer in self.layers.iter() {
+            if !layer.kind.blocks_movement() {
+                continue;
+            }
+            for tile in layer.tiles.iter() {
+                if tile.x < self.width && tile.y < self.depth {
+                    blocked[tile.y * self.width + tile.x] = true;
+                }
+            }
+        }
+
+        blocked
+    }
+
+    /// The cost of driving over each tile, indexed by `y * width + x`.
+    /// Every layer multiplies the cost of its tiles by its `cost_modifier`
+    pub fn tile_costs(&self) -> Vec<f32> {
+        let mut costs = vec![1.0; self.width * self.depth];
+        for layer in self.layers.iter() {
+            for tile in layer.tiles.iter() {
+                if tile.x < self.width && tile.y < self.depth {
+                    costs[tile.y * self.width + tile.x] *= layer.cost_modifier;
+                }
+            }
+        }
+        for cost in costs.iter_mut() {
+            *cost = cost.max(MIN_TILE_COST);
+        }
+
+        costs
+    }
+
+    /// The cheapest path between the tiles closest to two world positions, see [`Self::find_tile_path`]
+    pub fn find_path(&self, from: Vec3, to: Vec3, max_slope: f32) -> Option<TilePath> {
+        let start = self.get_closest_tile(from)?;
+        let goal = self.get_closest_tile(to)?;
+
+        self.find_tile_path(start, goal, max_slope)
+    }
+
+    /// The cheapest path between two tiles using A*.
+    /// Moves to all eight neighbours, but only cuts a corner if both tiles next to it can be driven on.
+    /// Returns None if a tank with this `max_slope` can't reach the goal
+    pub fn find_tile_path(
+        &self,
+        start: impl Into<TileDefinition>,
+        goal: impl Into<TileDefinition>,
+        max_slope: f32,
+    ) -> Option<TilePath> {
+        self.find_tile_path_around(start, goal, max_slope, &[])
+    }
+
+    /// Like [`Self::find_tile_path`], but `blocked` tiles can't be driven on either,
+    /// e.g. the tiles of the obstacles that are still standing
+    pub fn find_tile_path_around(
+        &self,
+        start: impl Into<TileDefinition>,
+        goal: impl Into<TileDefinition>,
+        max_slope: f32,
+        blocked: &[TileDefinition],
+    ) -> Option<TilePath> {
+        let (start, goal) = (start.into(), goal.into());
+        if start.x >= self.width
+            || start.y >= self.depth
+            || goal.x >= self.width
+            || goal.y >= self.depth
+        {
+            return None;
+        }
+
+        let costs = self.tile_costs();
+        let blocked = {
+            let mut blocked_by_layers = self.movement_blocked_tiles();
+            for tile in blocked.iter() {
+                if tile.x < self.width && tile.y < self.depth {
+                    blocked_by_layers[tile.y * self.width + tile.x] = true;
+                }
+            }
+            blocked_by_layers
+        };
+        let min_cost = costs.iter().copied().fold(f32::MAX, f32::min);
+        let index = |tile: &TileDefinition| tile.y * self.width + tile.x;
+        let can_drive_between = |from: &TileDefinition, to: &TileDefinition| {
+            !blocked[index(from)]
+                && !blocked[index(to)]
+                && self.can_climb_between(from.clone(), to.clone(), max_slope)
+        };
+        // Octile distance, never more than the real cost so the path stays the cheapest
+        let heuristic = |tile: &TileDefinition| {
+            let dx = tile.x.abs_diff(goal.x) as f32;
+            let dy = tile.y.abs_diff(goal.y) as f32;
+            (dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy)) * min_cost
+        };
+
+        let mut best_costs = vec![f32::MAX; self.width * self.depth];
+        let mut came_from: Vec<Option<usize>> = vec![None; self.width * self.depth];
+        let mut open = BinaryHeap::new();
+
+        best_costs[index(&start)] = 0.0;
+        open.push(OpenTile {
+            estimated_cost: heuristic(&start),
+            index: index(&start),
+        });
+
+        while let Some(OpenTile {
+            estimated_cost,
+            index: current,
+        }) = open.pop()
+        {
+            let tile = TileDefinition {
+                x: current % self.width,
+                y: current / self.width,
+            };
+            if tile == goal {
+                let mut tiles = vec![tile];
+                let mut previous = came_from[current];
+                while let Some(previous_index) = previous {
+                    tiles.push(TileDefinition {
+                        x: previous_index % self.width,
+                        y: previous_index / self.width,
+                    });
+                    previous = came_from[previous_index];
+                }
+                tiles.reverse();
+
+                return Some(TilePath {
+                    tiles,
+                    cost: best_costs[current],
+                });
+            }
+            // Already reached this tile in a cheaper way
+            if estimated_cost > best_costs[current] + heuristic(&tile) {
+                continue;
+            }
+
+            let neighbours = self.get_neighbours(tile.clone());
+            let straight = [
+                &neighbours.north,
+                &neighbours.east,
+                &neighbours.south,
+                &neighbours.west,
+            ];
+            let diagonal = [
+                (&neighbours.north_east, &neighbours.north, &neighbours.east),
+                (&neighbours.north_west, &neighbours.north, &neighbours.west),
+                (&neighbours.south_east, &neighbours.south, &neighbours.east),
+                (&neighbours.south_west, &neighbours.south, &neighbours.west),
+            ];
+
+            let mut steps = Vec::with_capacity(8);
+            for neighbour in straight.into_iter().flatten() {
+                if can_drive_between(&tile, neighbour) {
+                    steps.push((neighbour.clone(), 1.0));
+                }
+            }
+            for (neighbour, side_a, side_b) in diagonal {
+                let (Some(neighbour), Some(side_a), Some(side_b)) = (neighbour, side_a, side_b)
+                else {
+                    continue;
+                };
+                let corner_is_free = [side_a, side_b].into_iter().all(|side| {
+                    can_drive_between(&tile, side) && can_drive_between(side, neighbour)
+                });
+                if corner_is_free {
+                    steps.push((neighbour.clone(), std::f32::consts::SQRT_2));
+                }
+            }
+
+            for (neighbour, distance) in steps {
+                let neighbour_index = index(&neighbour);
+                // Half of each tile is driven on, so average the costs of both
+                let cost = best_costs[current]
+                    + distance * (costs[current] + costs[neighbour_index]) / 2.0;
+                if cost < best_costs[neighbour_index] {
+                    best_costs[neighbour_index] = cost;
+                    came_from[neighbour_index] = Some(current);
+                    open.push(OpenTile {
+                        estimated_cost: cost + heuristic(&neighbour),
+                        index: neighbour_index,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asset_handling::maps::{LayerDefinition, LayerType};
+
+    use super::*;
+
+    fn flat_map(width: usize, depth: usize) -> MapDefinition {
+        MapDefinition {
+            width,
+            depth,
+            tiles: vec![vec![0.0; width]; depth],
+            ..default()
+        }
+    }
+
+    fn tiles(tiles: &[(usize, usize)]) -> Vec<TileDefinition> {
+        tiles.iter().map(|tile| (*tile).into()).collect()
+    }
+
+    #[test]
+    fn drives_straight_over_flat_ground() {
+        let map = flat_map(5, 5);
+
+        let path = map.find_tile_path((0, 0), (4, 0), 1.0).unwrap();
+
+        assert_eq!(path.tiles, tiles(&[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]));
+        assert_eq!(path.cost, 4.0);
+    }
+
+    #[test]
+    fn drives_diagonally_over_flat_ground() {
+        let map = flat_map(5, 5);
+
+        let path = map.find_tile_path((0, 0), (3, 3), 1.0).unwrap();
+
+        assert_eq!(path.tiles, tiles(&[(0, 0), (1, 1), (2, 2), (3, 3)]));
+        assert!((path.cost - 3.0 * std::f32::consts::SQRT_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn drives_around_walls_without_cutting_their_corners() {
+        let mut map = flat_map(5, 3);
+        map.layers.push(LayerDefinition {
+            kind: LayerType::Wall,
+            cost_modifier: 1.0,
+            tiles: tiles(&[(2, 0), (2, 1)]),
+        });
+
+        let path = map.find_tile_path((0, 0), (4, 0), 1.0).unwrap();
+
+        assert!(path.tiles.contains(&(2, 2).into()));
+        assert!(!path.tiles.contains(&(2, 0).into()));
+        assert!(!path.tiles.contains(&(2, 1).into()));
+        // Both corners of the wall are driven around
+        assert!(path.tiles.contains(&(1, 2).into()));
+        assert!(path.tiles.contains(&(3, 2).into()));
+    }
+
+    #[test]
+    fn prefers_cheaper_layers() {
+        let mut map = flat_map(3, 3);
+        map.layers.push(LayerDefinition {
+            kind: LayerType::Mud,
+            cost_modifier: 10.0,
+            tiles: tiles(&[(1, 0)]),
+        });
+
+        let path = map.find_tile_path((0, 0), (2, 0), 1.0).unwrap();
+
+        assert!(!path.tiles.contains(&(1, 0).into()));
+    }
+
+    #[test]
+    fn cant_climb_cliffs_steeper_than_the_max_slope() {
+        let mut map = flat_map(4, 1);
+        map.tiles[0][2] = 2.0;
+
+        assert_eq!(map.find_tile_path((0, 0), (3, 0), 1.0), None);
+        assert!(map.find_tile_path((0, 0), (3, 0), 2.0).is_some());
+    }
+
+    #[test]
+    fn finds_no_path_outside_the_map() {
+        let map = flat_map(3, 3);
+
+        assert_eq!(map.find_tile_path((0, 0), (3, 0), 1.0), None);
+        assert_eq!(map.find_tile_path((0, 5), (0, 0), 1.0), None);
+    }
+
+    #[test]
+    fn drives_around_blocked_tiles() {
+        let map = flat_map(3, 1);
+
+        assert_eq!(
+            map.find_tile_path_around((0, 0), (2, 0), 1.0, &tiles(&[(1, 0)])),
+            None
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Generates a map from a seed, e.g. `cargo run -p shared --bin generate_map -- assets/maps/hills.map.ron --seed 42`
+//! Run with `--help` to see all parameters
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use shared::asset_handling::{
+    config::TankConfigs,
+    map_generator::{MapGeneratorConfig, MapSymmetry},
+};
+
+const USAGE: &str = "Usage: generate_map <output.map.ron> [options]
+
+Options:
+    --seed <u64>
+    --width <tiles>
+    --depth <tiles>
+    --roughness <height>
+    --hills <count>
+    --hill-height <height>
+    --forest <share from 0.0 to 1.0>
+    --teams <2 or 4>
+    --players <per team>
+    --symmetry <mirror or rotational>
+    --tanks <path to config.tanks.ron, used to check that every flag can be reached>";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut output = None;
+    let mut tanks_path = PathBuf::from("assets/config/config.tanks.ron");
+    let mut config = MapGeneratorConfig::default();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        if !arg.starts_with("--") {
+            output = Some(PathBuf::from(arg));
+            continue;
+        }
+
+        let Some(value) = args.next() else {
+            eprintln!("Missing value for {}\n\n{}", arg, USAGE);
+            return ExitCode::FAILURE;
+        };
+        let parsed = match arg.as_str() {
+            "--seed" => value.parse().map(|seed| config.seed = seed).ok(),
+            "--width" => value.parse().map(|width| config.width = width).ok(),
+            "--depth" => value.parse().map(|depth| config.depth = depth).ok(),
+            "--roughness" => value
+                .parse()
+                .map(|roughness| config.roughness = roughness)
+                .ok(),
+            "--hills" => value.parse().map(|hills| config.hills = hills).ok(),
+            "--hill-height" => value
+                .parse()
+                .map(|height| config.max_hill_height = height)
+                .ok(),
+            "--forest" => value
+                .parse()
+                .map(|density| config.forest_density = density)
+                .ok(),
+            "--teams" => value
+                .parse()
+                .map(|team_count| config.team_count = team_count)
+                .ok(),
+            "--players" => value
+                .parse()
+                .map(|players| config.players_per_team = players)
+                .ok(),
+            "--symmetry" => match value.as_str() {
+                "mirror" => Some(MapSymmetry::Mirror),
+                "rotational" => Some(MapSymmetry::Rotational),
+                _ => None,
+            }
+            .map(|symmetry| config.symmetry = symmetry),
+            "--tanks" => {
+                tanks_path = PathBuf::from(&value);
+                Some(())
+            }
+            _ => {
+                eprintln!("Unknown option {}\n\n{}", arg, USAGE);
+                return ExitCode::FAILURE;
+            }
+        };
+        if parsed.is_none() {
+            eprintln!("Invalid value \"{}\" for {}", value, arg);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let Some(output) = output else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let tank_configs = match fs::read_to_string(&tanks_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| ron::from_str::<TankConfigs>(&content).map_err(|e| e.to_string()))
+    {
+        Ok(tank_configs) => tank_configs,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", tanks_path.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let map_config = match config.generate(&tank_configs) {
+        Ok(map_config) => map_config,
+        Err(e) => {
+            eprintln!("Failed to generate the map: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match map_config
+        .to_ron_string()
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(&output, content).map_err(|e| e.to_string()))
+    {
+        Ok(()) => {
+            println!("Wrote {} ({:?})", output.display(), config);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", output.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
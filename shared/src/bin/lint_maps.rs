@@ -0,0 +1,62 @@
+//! Checks every map in a directory, e.g. `cargo run -p shared --bin lint_maps -- assets/maps`
+//! Exits with an error if any map can't be parsed or is invalid
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use shared::asset_handling::maps::MapConfig;
+
+fn main() -> ExitCode {
+    let maps_directory = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("assets/maps"));
+
+    let mut map_files = match fs::read_dir(&maps_directory) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(".map.ron"))
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", maps_directory.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+    map_files.sort();
+
+    let mut invalid_maps = 0;
+    for map_file in map_files.iter() {
+        let map_config = fs::read_to_string(map_file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| ron::from_str::<MapConfig>(&content).map_err(|e| e.to_string()));
+
+        match map_config.map(|map_config| map_config.validate()) {
+            Ok(Ok(())) => println!("{}: ok", map_file.display()),
+            Ok(Err(errors)) => {
+                invalid_maps += 1;
+                println!("{}: {} errors", map_file.display(), errors.len());
+                for error in errors {
+                    println!("\t{}", error);
+                }
+            }
+            Err(e) => {
+                invalid_maps += 1;
+                println!("{}: failed to parse: {}", map_file.display(), e);
+            }
+        }
+    }
+
+    println!(
+        "{} of {} maps are valid",
+        map_files.len() - invalid_maps,
+        map_files.len()
+    );
+    if invalid_maps > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
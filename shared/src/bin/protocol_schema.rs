@@ -0,0 +1,10 @@
+//! Prints the JSON Schema of the protocol, e.g. `cargo run -p shared --bin protocol_schema > protocol.schema.json`
+
+use shared::networking::messages::protocol_schema::protocol_schema;
+
+fn main() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&protocol_schema()).expect("Failed to format protocol schema")
+    );
+}
@@ -34,6 +34,7 @@ impl CollisionLayer {
     pub const PLAYER: u32 = 1;
     pub const FLAG: u32 = 2;
     pub const FLAG_BASE: u32 = 3;
+    pub const OBSTACLE: u32 = 4;
 
     /// Create a collision layer from a list of layer indices.
     /// Each index in the list will be set as a bit in the mask.
@@ -55,6 +56,11 @@ impl CollisionLayer {
         Self::new(&[Self::FLAG_BASE])
     }
 
+    /// Create a collision layer for obstacle
+    pub fn obstacle() -> Self {
+        Self::new(&[Self::OBSTACLE])
+    }
+
     /// Create a collision layer for player
     pub fn player() -> Self {
         Self::new(&[Self::PLAYER])
@@ -2,11 +2,14 @@ use bevy::{
     math::{Mat3A, Vec3A},
     prelude::*,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::components::Collider;
 
-#[derive(Debug, Default, Clone, Copy, Reflect, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(
+    Debug, Default, Clone, Copy, Reflect, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash,
+)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Side {
     Top,
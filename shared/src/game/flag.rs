@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Reflect, Component)]
@@ -21,12 +22,79 @@ pub struct FlagCarrier {
     pub flag: Entity,
 }
 
-#[derive(Debug, Clone, Default, Reflect, Component, Serialize, Deserialize, PartialEq)]
+#[derive(
+    Debug, Clone, Default, Reflect, Component, Serialize, Deserialize, JsonSchema, PartialEq,
+)]
 #[reflect(Component)]
 #[serde(rename_all = "PascalCase", tag = "state", content = "entityId")]
 pub enum FlagState {
     #[default]
     InBase,
-    Carried(Entity),
+    Carried(#[schemars(with = "u64")] Entity),
     Dropped,
 }
+
+/// The ticks left until a dropped flag returns to its base on its own
+#[derive(Debug, Clone, Reflect, Component, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct FlagReturnTimer(pub u64);
+
+/// The capture the flag rules of a lobby, set when the game starts.
+/// The keys are camelCase like the rest of the protocol, the config files can also use snake_case
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlagRules {
+    /// A dropped flag returns to its base after lying on the ground for this many ticks.
+    /// If None, it stays there until a tank touches it
+    #[serde(alias = "return_after_ticks")]
+    pub return_after_ticks: Option<u64>,
+    /// Multiplies the move speed of tanks carrying a flag
+    #[serde(alias = "carrier_speed_modifier")]
+    pub carrier_speed_modifier: f32,
+    /// Multiplies the turret rotation speed of tanks carrying a flag
+    #[serde(alias = "carrier_turret_speed_modifier")]
+    pub carrier_turret_speed_modifier: f32,
+    /// If true, a team can only score while its own flag is in its base
+    #[serde(alias = "own_flag_in_base_to_score")]
+    pub own_flag_in_base_to_score: bool,
+    /// How close (horizontally, in tiles) a tank has to get to a flag to pick it up or return it
+    #[serde(alias = "pickup_radius")]
+    pub pickup_radius: f32,
+    /// How close (horizontally, in tiles) a carried flag has to get to a flag base to score
+    #[serde(alias = "capture_radius")]
+    pub capture_radius: f32,
+}
+
+impl Default for FlagRules {
+    fn default() -> Self {
+        Self {
+            return_after_ticks: None,
+            carrier_speed_modifier: 1.0,
+            carrier_turret_speed_modifier: 1.0,
+            own_flag_in_base_to_score: true,
+            pickup_radius: 0.25,
+            capture_radius: 0.25,
+        }
+    }
+}
+
+impl FlagRules {
+    /// Negative or infinite speeds and radii that can't be reached don't make sense
+    pub fn validate(&self) -> Result<(), String> {
+        let valid_modifier = |modifier: f32| modifier.is_finite() && modifier >= 0.0;
+        if !valid_modifier(self.carrier_speed_modifier)
+            || !valid_modifier(self.carrier_turret_speed_modifier)
+        {
+            return Err(
+                "The speed modifiers of flag carriers have to be finite and can't be negative"
+                    .to_string(),
+            );
+        }
+        let valid_radius = |radius: f32| radius.is_finite() && radius > 0.0;
+        if !valid_radius(self.pickup_radius) || !valid_radius(self.capture_radius) {
+            return Err("The pickup and capture radius have to be finite and positive".to_string());
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::networking::messages::message_data::game_state::GameState;
+use crate::{
+    asset_handling::maps::{ObstacleType, TileDefinition},
+    networking::messages::message_data::game_state::GameState,
+};
 
 use super::player_handling::PlayerState;
 
@@ -18,6 +22,8 @@ pub struct LobbyGameState {
     pub projectiles: HashMap<Entity, ProjectileState>,
     pub flags: HashMap<Entity, FlagGameState>,
     pub flag_bases: HashMap<Entity, FlagBaseState>,
+    /// The obstacles that are still standing
+    pub obstacles: HashMap<Entity, ObstacleState>,
 }
 
 impl LobbyGameState {
@@ -42,6 +48,7 @@ impl From<LobbyGameState> for GameState {
             projectile_states: lobby_game_state.projectiles,
             flag_states: lobby_game_state.flags,
             flag_base_states: lobby_game_state.flag_bases,
+            obstacle_states: lobby_game_state.obstacles,
         }
     }
 }
@@ -59,6 +66,7 @@ pub struct PersonalizedClientGameState {
     pub projectiles: HashMap<Entity, ProjectileState>,
     pub flags: HashMap<Entity, FlagGameState>,
     pub flag_bases: HashMap<Entity, FlagBaseState>,
+    pub obstacles: HashMap<Entity, ObstacleState>,
 }
 
 impl PersonalizedClientGameState {
@@ -93,25 +101,30 @@ impl From<PersonalizedClientGameState> for GameState {
             projectile_states: personalized_client_game_state.projectiles,
             flag_states: personalized_client_game_state.flags,
             flag_base_states: personalized_client_game_state.flag_bases,
+            obstacle_states: personalized_client_game_state.obstacles,
         }
     }
 }
 
 /// The state of a client
 /// Can be personalized for each client, depending on the information the client knows about the other clients
-#[derive(Debug, Reflect, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ClientState {
     /// This is the entity id of the client
+    #[schemars(with = "u64")]
     pub id: Entity,
     /// The position and rotation of the clients body.
+    /// On slopes the body is pitched and rolled to follow the ground.
     /// None if the client that receives this state does not know the position of the client.
     /// e.g. because the client has not spotted the other client yet.
+    #[schemars(with = "Option<crate::networking::messages::protocol_schema::TransformSchema>")]
     pub transform_body: Option<Transform>,
     /// The position and rotation of the clients turret.
     /// Relative to the body.
     /// None if the client that receives this state does not know the position of the client.
     /// e.g. because the client has not spotted the other client yet.
+    #[schemars(with = "Option<crate::networking::messages::protocol_schema::TransformSchema>")]
     pub transform_turret: Option<Transform>,
     /// The state of the player (e.g. alive, dead)
     pub state: Option<PlayerState>,
@@ -119,6 +132,10 @@ pub struct ClientState {
     pub shoot_cooldown: u32,
     /// The current health of the client
     pub current_health: f32,
+    /// The round trip time between the server and the client in milliseconds, if it was measured already
+    pub rtt_ms: Option<f32>,
+    /// How many ticks the messages of the client are behind on average (tick received - tick sent)
+    pub tick_offset: Option<f32>,
 }
 
 impl ClientState {
@@ -130,6 +147,8 @@ impl ClientState {
             state: None,
             shoot_cooldown: 0,
             current_health: 100.0,
+            rtt_ms: None,
+            tick_offset: None,
         }
     }
 
@@ -152,15 +171,20 @@ impl Default for ClientState {
             state: None,
             shoot_cooldown: 0,
             current_health: 100.0,
+            rtt_ms: None,
+            tick_offset: None,
         }
     }
 }
 
-#[derive(Debug, Reflect, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectileState {
+    #[schemars(with = "u64")]
     pub projectile_id: Entity,
+    #[schemars(with = "u64")]
     pub owner_id: Entity,
+    #[schemars(with = "crate::networking::messages::protocol_schema::TransformSchema")]
     pub transform: Transform,
 }
 
@@ -174,13 +198,17 @@ impl ProjectileState {
     }
 }
 
-#[derive(Debug, Reflect, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FlagGameState {
+    #[schemars(with = "u64")]
     pub flag_id: Entity,
+    #[schemars(with = "u64")]
     pub flag_base_id: Entity,
     pub team: String,
+    #[schemars(with = "crate::networking::messages::protocol_schema::TransformSchema")]
     pub transform: Transform,
+    #[schemars(with = "[f32; 3]")]
     pub collider_size: Vec3,
     pub state: super::flag::FlagState,
 }
@@ -205,13 +233,33 @@ impl FlagGameState {
     }
 }
 
-#[derive(Debug, Reflect, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Reflect, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FlagBaseState {
+    #[schemars(with = "u64")]
     pub flag_id: Entity,
+    #[schemars(with = "u64")]
     pub flag_base_id: Entity,
     pub team: String,
+    #[schemars(with = "crate::networking::messages::protocol_schema::TransformSchema")]
     pub transform: Transform,
+    #[schemars(with = "[f32; 3]")]
     pub collider_size: Vec3,
     pub flag_in_base: bool,
 }
+
+#[derive(Debug, Reflect, Serialize, Deserialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObstacleState {
+    #[schemars(with = "u64")]
+    pub obstacle_id: Entity,
+    pub obstacle_type: ObstacleType,
+    /// The tile it blocks, see [`crate::asset_handling::maps::MapDefinition::find_tile_path_around`]
+    pub tile: TileDefinition,
+    #[schemars(with = "crate::networking::messages::protocol_schema::TransformSchema")]
+    pub transform: Transform,
+    #[schemars(with = "[f32; 3]")]
+    pub collider_size: Vec3,
+    pub current_health: f32,
+    pub max_health: f32,
+}
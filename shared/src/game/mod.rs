@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use collision_handling::MyCollisionHandlingPlugin;
-use game_state::{ClientState, LobbyGameState, PersonalizedClientGameState, ProjectileState};
+use game_state::{
+    ClientState, LobbyGameState, ObstacleState, PersonalizedClientGameState, ProjectileState,
+};
 use player_handling::{
     Health, PlayerState, RespawnTimer, ShootCooldown, TankBodyMarker, TankTurretMarker,
 };
@@ -11,6 +13,7 @@ pub mod common_components;
 pub mod common_systems;
 pub mod flag;
 pub mod game_state;
+pub mod obstacle;
 pub mod player_handling;
 pub mod projectile_handling;
 pub mod tank_types;
@@ -23,6 +26,7 @@ impl Plugin for MySharedGamePlugin {
             .register_type::<PersonalizedClientGameState>()
             .register_type::<ClientState>()
             .register_type::<ProjectileState>()
+            .register_type::<ObstacleState>()
             .register_type::<Health>()
             .register_type::<RespawnTimer>()
             .register_type::<TankBodyMarker>()
@@ -39,6 +43,9 @@ impl Plugin for MySharedGamePlugin {
             .register_type::<flag::FlagState>()
             .register_type::<flag::FlagBaseMarker>()
             .register_type::<flag::FlagCarrier>()
+            .register_type::<flag::FlagReturnTimer>()
+            .register_type::<flag::FlagRules>()
+            .register_type::<obstacle::ObstacleMarker>()
             .add_plugins((MyCollisionHandlingPlugin,))
             .add_systems(
                 Update,
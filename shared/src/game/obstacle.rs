@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+
+use crate::asset_handling::maps::{ObstacleType, TileDefinition};
+
+use super::player_handling::Health;
+
+/// A destructible object placed by an obstacle marker of the map
+/// Tanks can't drive onto its tile until its health drops to zero
+#[derive(Debug, Clone, Reflect, Component)]
+#[reflect(Component)]
+#[require(Health)]
+pub struct ObstacleMarker {
+    pub obstacle_type: ObstacleType,
+    pub tile: TileDefinition,
+}
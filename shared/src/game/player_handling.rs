@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::asset_handling::config::TankConfigSystemParam;
@@ -8,7 +9,9 @@ use super::{
     tank_types::TankType,
 };
 
-#[derive(Debug, Reflect, Component, Clone, PartialEq, Default, Serialize, Deserialize, Copy)]
+#[derive(
+    Debug, Reflect, Component, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema, Copy,
+)]
 #[reflect(Component)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PlayerState {
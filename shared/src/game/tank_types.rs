@@ -1,8 +1,19 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(
-    Debug, Default, Reflect, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Component,
+    Debug,
+    Default,
+    Reflect,
+    Clone,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    Hash,
+    Component,
 )]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[reflect(Component)]
@@ -9,7 +9,7 @@ use crate::{
     },
 };
 
-use super::{MyLobbies, MyLobby};
+use super::{GameMode, MyLobbies, MyLobby};
 
 #[derive(Debug, Default, Clone)]
 pub struct LobbyManagementArgument {
@@ -31,6 +31,7 @@ impl<'w, 's> LobbyManagementSystemParam<'w, 's> {
         &mut self,
         lobby_id: &str,
         map_name: Option<&str>,
+        game_mode: GameMode,
         commands: &mut Commands,
         server_config: &ServerConfig,
     ) -> Result<Entity, ()> {
@@ -45,7 +46,8 @@ impl<'w, 's> LobbyManagementSystemParam<'w, 's> {
                     let entity = commands
                         .spawn((
                             Name::new(format!("Lobby_{}_{}", lobby_id, map_name)),
-                            MyLobby::new(lobby_id.to_string(), map_name, server_config.tick_rate),
+                            MyLobby::new(lobby_id.to_string(), map_name, server_config.tick_rate)
+                                .with_game_mode(game_mode),
                         ))
                         .id();
 
@@ -2,13 +2,16 @@ use std::time::Duration;
 
 use bevy::{prelude::*, utils::HashMap};
 use lobby_management::LobbyManagementSystemParam;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     asset_handling::{
         config::ServerConfigSystemParam,
+        layer_grid::LayerGrid,
         maps::{MapConfig, MapConfigSystemParam},
     },
-    game::game_state::LobbyGameState,
+    game::{flag::FlagRules, game_state::LobbyGameState},
     networking::messages::{
         message_container::{MessageContainer, MessageTarget, NetworkMessageType},
         message_data::{message_error_types::ErrorMessageTypes, text_data::TextDataWrapper},
@@ -34,6 +37,7 @@ impl Plugin for MyLobbyManagementPlugin {
             .register_type::<InLobby>()
             .register_type::<InTeam>()
             .register_type::<LobbyState>()
+            .register_type::<GameMode>()
             .register_type::<AwaitingFirstContact>()
             .add_observer(finish_setting_up_lobby)
             .add_observer(adding_player_to_lobby)
@@ -107,14 +111,26 @@ pub struct MyLobby {
     pub projectiles: Vec<Entity>,
     pub flags: Vec<Entity>,
     pub flag_bases: Vec<Entity>,
+    /// The obstacles that are still standing
+    pub obstacles: Vec<Entity>,
 
     pub map_name: String,
     pub map_config: Option<MapConfig>,
+    /// The layers of the map per tile, set together with the map config
+    pub layer_grid: LayerGrid,
+    pub game_mode: GameMode,
 
     /// Timer for ticking the lobby
     pub tick_timer: Timer,
     /// The currently, finished tick
     pub tick_processed: u64,
+    /// The game ends after this tick, if set
+    pub max_ticks: Option<u64>,
+    /// If set, the lobby runs in lockstep. Used as the timeout for the players to end their turn
+    pub lockstep_timer: Option<Timer>,
+    /// If true, the lobby ticks as soon as all players ended their turn, without waiting for the tick timer
+    pub fast_forward: bool,
+    pub flag_rules: FlagRules,
     /// All unprocessed messages received by the lobby (will be processed in the next tick, or dropped if the messages are too old)
     pub messages: MessageQueue,
 }
@@ -132,12 +148,19 @@ impl MyLobby {
             projectiles: Vec::new(),
             flags: Vec::new(),
             flag_bases: Vec::new(),
+            obstacles: Vec::new(),
 
             map_name,
             map_config: None,
+            layer_grid: LayerGrid::default(),
+            game_mode: GameMode::default(),
 
             tick_timer: Timer::from_seconds(time_per_tick, TimerMode::Repeating),
             tick_processed: 0,
+            max_ticks: None,
+            lockstep_timer: None,
+            fast_forward: false,
+            flag_rules: FlagRules::default(),
 
             messages: MessageQueue::default(),
         }
@@ -153,6 +176,16 @@ impl MyLobby {
         self
     }
 
+    pub fn with_game_mode(mut self, game_mode: GameMode) -> Self {
+        self.game_mode = game_mode;
+        self
+    }
+
+    pub fn set_map_config(&mut self, map_config: MapConfig) {
+        self.layer_grid = LayerGrid::new(&map_config.map);
+        self.map_config = Some(map_config);
+    }
+
     pub fn get_team(&self, team_name: &str) -> Option<&Vec<Entity>> {
         self.map_config
             .as_ref()
@@ -164,15 +197,30 @@ impl MyLobby {
     }
 }
 
-#[derive(Debug, Reflect, Default, PartialEq)]
+#[derive(Debug, Reflect, Default, PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum LobbyState {
     #[default]
     SettingUp,
     ReadyToStart,
     InProgress,
+    /// Started, but doesn't tick until it gets resumed
+    Paused,
     Finished,
 }
 
+#[derive(
+    Debug, Reflect, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GameMode {
+    /// Players choose their team when joining
+    #[default]
+    Teams,
+    /// Every team of the map has room for one player, and players get the next free team when joining
+    FreeForAll,
+}
+
 pub fn remove_player_from_lobby(
     trigger: Trigger<PlayerRemovedFromLobbyTrigger>,
     mut commands: Commands,
@@ -212,7 +260,7 @@ fn adding_player_to_lobby(
         let mut queue = player_immediate_message_queues.get_mut(*player).unwrap();
 
         match lobby.state {
-            LobbyState::InProgress | LobbyState::Finished => {
+            LobbyState::InProgress | LobbyState::Paused | LobbyState::Finished => {
                 error!(
                     "Player {:?} wants to join lobby {:?} but it is in state {:?}",
                     player, lobby_entity, lobby.state
@@ -234,6 +282,15 @@ fn adding_player_to_lobby(
 
         match player_type {
             ClientType::Player => {
+                // In free-for-all lobbies the server picks the team
+                let team_name = match lobby.game_mode {
+                    GameMode::FreeForAll => lobby
+                        .map_config
+                        .as_ref()
+                        .and_then(|map_config| map_config.first_free_team()),
+                    GameMode::Teams => team_name.clone(),
+                };
+
                 if let Some(team_name) = team_name {
                     lobby
                         .players
@@ -243,7 +300,7 @@ fn adding_player_to_lobby(
                         .map_config
                         .as_mut()
                         .expect("Map config should be set up by now")
-                        .insert_player_into_team(team_name, *player)
+                        .insert_player_into_team(&team_name, *player)
                     {
                         Ok(_) => {
                             commands
@@ -267,6 +324,16 @@ fn adding_player_to_lobby(
                             return;
                         }
                     }
+                } else if lobby.game_mode == GameMode::FreeForAll {
+                    error!("Player wants to join a free-for-all lobby, but all teams are taken");
+                    queue.push_back(MessageContainer::new(
+                        MessageTarget::Client(*player),
+                        NetworkMessageType::MessageError(ErrorMessageTypes::TeamFull(
+                            "All teams of the free-for-all lobby are taken".to_string(),
+                        )),
+                    ));
+
+                    return;
                 } else {
                     error!("Player wants to join lobby without specifying a team name");
                     queue.push_back(MessageContainer::new(
@@ -283,6 +350,10 @@ fn adding_player_to_lobby(
                 lobby.spectators.push(*player);
             }
             ClientType::Dummy => unimplemented!("Dummy clients should not be able to join lobbies"),
+            ClientType::Admin => unimplemented!("Admin clients should not be able to join lobbies"),
+            ClientType::Observer => {
+                unimplemented!("Observer clients should not be able to join lobbies")
+            }
         }
 
         commands
@@ -307,7 +378,11 @@ fn finish_setting_up_lobby(
                 lobby.map_name, lobby.lobby_name
             );
 
-            lobby.map_config = Some(map_config.clone());
+            let mut map_config = map_config.clone();
+            if lobby.game_mode == GameMode::FreeForAll {
+                map_config.make_free_for_all();
+            }
+            lobby.set_map_config(map_config);
 
             lobby.state = LobbyState::ReadyToStart;
         } else {
@@ -1,22 +1,31 @@
 use bevy::prelude::*;
 use proc_macros::{auto_trigger_message_received, generate_message_data_triggers};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     game::player_handling::PlayerState,
     networking::{
         lobby_management::lobby_management::{LobbyManagementArgument, LobbyManagementSystemParam},
-        messages::message_queue::OutMessageQueue,
+        messages::{message_queue::OutMessageQueue, protocol_schema::MessageTypeDescription},
     },
 };
 
 use super::message_data::{
+    admin::{AdminCommand, LobbyListData},
+    command_results::CommandResultsData,
+    end_turn::EndTurnData,
     entity_data::EntityDataWrapper,
     first_contact::FirstContactData,
     flag_event_data::{FlagEventDataWrapper, FlagSimpleEventDataWrapper},
+    game_finished::GameFinishedData,
     game_starts::GameStarts,
     game_state::GameState,
     message_error_types::ErrorMessageTypes,
+    obstacle_destroyed::ObstacleDestroyedData,
+    ping::PingData,
+    rating::{RatingsData, RatingsRequest},
+    server_info::{ServerInfoData, ServerInfoRequest},
     start_game_config::StartGameConfig,
     tank_messages::{
         hit_message_data::{GotHitMessageData, HitMessageData},
@@ -27,13 +36,14 @@ use super::message_data::{
     },
     team_scored::TeamScoredData,
     text_data::TextDataWrapper,
+    tournament::{TournamentStandingsData, TournamentStandingsRequest},
 };
 
-#[derive(Serialize, Deserialize, Default, Reflect, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Default, Reflect, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[auto_trigger_message_received(
     target = {
-        #[derive(Serialize, Deserialize, Default, Reflect, Clone, Debug, PartialEq)]
+        #[derive(Serialize, Deserialize, JsonSchema, Default, Reflect, Clone, Debug, PartialEq)]
         #[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "type", content = "clientId")]
         pub enum MessageTarget {
             #[default]
@@ -48,7 +58,7 @@ use super::message_data::{
             AllInLobby,
             #[get_targets(targets_get_single_player)]
             // To a single player, excluding the the sender itself
-            Client(Entity),
+            Client(#[schemars(with = "u64")] Entity),
             #[get_targets(targets_get_lobby_directly)]
             // To the lobby itself (for example to start the game)
             ToLobbyDirectly,
@@ -58,7 +68,7 @@ use super::message_data::{
         }
     },
     message = {
-        #[derive(Serialize, Deserialize, Reflect, Clone, Debug, PartialEq)]
+        #[derive(Serialize, Deserialize, JsonSchema, Reflect, Clone, Debug, PartialEq)]
         #[serde(tag = "message_type")]
         #[generate_message_data_triggers]
         pub enum NetworkMessageType {
@@ -91,6 +101,9 @@ use super::message_data::{
             /// Sent to the client when they successfully joined a lobby
             /// Can not be sent by a client, only by the server
             SuccessfullyJoinedLobby(TextDataWrapper),
+            /// Sent to the client when they got queued for matchmaking, because they didn't name a lobby
+            /// Can not be sent by a client, only by the server
+            QueuedForMatchmaking(TextDataWrapper),
             /// Sent from the client to the server to move the tank
             /// Will only be sent by a client
             /// Can only be sent to itself on the server
@@ -110,6 +123,15 @@ use super::message_data::{
             #[player_state(Alive)]
             #[unique]
             ShootCommand(ShootCommand),
+            /// Sent by a player once it sent all its commands for the current tick
+            /// In lockstep lobbies, the next tick starts as soon as every player ended its turn
+            /// Can only be sent to itself on the server
+            #[target(ToSelf)]
+            #[unique]
+            EndTurn(EndTurnData),
+            /// Sent to every player after each tick, lists what happened to the commands it sent
+            /// Can not be sent by a client, only by the server
+            CommandResults(CommandResultsData),
             GotHit(GotHitMessageData),
             Hit(HitMessageData),
             PlayerDied(EntityDataWrapper),
@@ -121,6 +143,55 @@ use super::message_data::{
             /// Sent when a flag was returned to its base
             FlagReturnedInBase(FlagSimpleEventDataWrapper),
             TeamScored(TeamScoredData),
+            /// Sent to everyone in the lobby when an obstacle got shot down
+            /// Can not be sent by a client, only by the server
+            ObstacleDestroyed(ObstacleDestroyedData),
+            /// Sent to everyone in the lobby when the game is over, contains the final scores
+            /// Can not be sent by a client, only by the server
+            GameFinished(GameFinishedData),
+            /// Sent by a client to get the current standings of the running tournament
+            #[target(ServerOnly)]
+            RequestTournamentStandings(TournamentStandingsRequest),
+            /// The answer to a RequestTournamentStandings message
+            /// Can not be sent by a client, only by the server
+            TournamentStandings(TournamentStandingsData),
+            /// Sent by a client to get the ratings of bots
+            #[target(ServerOnly)]
+            RequestRatings(RatingsRequest),
+            /// The answer to a RequestRatings message
+            /// Can not be sent by a client, only by the server
+            Ratings(RatingsData),
+            /// Sent by a client to get the available maps and the existing lobbies
+            /// Works before the first contact, so a bot can pick a lobby before joining it
+            #[target(ServerOnly)]
+            RequestServerInfo(ServerInfoRequest),
+            /// The answer to a RequestServerInfo message
+            /// Can not be sent by a client, only by the server
+            ServerInfo(ServerInfoData),
+            /// Sent by an admin client to operate the server, e.g. pause a lobby or kick a client
+            /// Ignored if the sender isn't an authenticated admin
+            #[target(ServerOnly)]
+            AdminCommand(AdminCommand),
+            /// Sent to an admin client when a command was executed, or when it got authenticated
+            /// Can not be sent by a client, only by the server
+            AdminCommandDone(TextDataWrapper),
+            /// Sent to everyone in the lobby when the game got paused. Nothing moves until it gets resumed
+            /// Can not be sent by a client, only by the server
+            LobbyPaused(TextDataWrapper),
+            /// Sent to everyone in the lobby when the paused game continues
+            /// Can not be sent by a client, only by the server
+            LobbyResumed(TextDataWrapper),
+            /// Sent by the server about once a second, the client has to answer with a Pong with the same id
+            /// Clients can send a Ping to the server as well, it answers with a Pong
+            /// Gets handled right away, not in the next tick
+            #[target(ServerOnly)]
+            Ping(PingData),
+            /// The answer to a Ping, used to measure the round trip time
+            #[target(ServerOnly)]
+            Pong(PingData),
+            /// The answer to the ListLobbies admin command
+            /// Can not be sent by a client, only by the server
+            LobbyList(LobbyListData),
         }
     }
 )]
@@ -128,12 +199,17 @@ pub struct MessageContainer {
     pub target: MessageTarget,
     pub message: NetworkMessageType,
 
+    #[schemars(with = "Option<u64>")]
     pub sender: Option<Entity>,
 
     /// The tick when the message was sent
     pub tick_sent: u64,
     /// The tick when the message was received
     pub tick_received: u64,
+    /// The tick the sender wants the message to be processed at. If None, it gets processed in the next tick.
+    /// Has to be in the future, but not more than `max_ticks_ahead` (server config) ticks
+    #[serde(default)]
+    pub tick_target: Option<u64>,
     /// The tick when the message should be processed at on the server
     #[serde(skip)]
     pub tick_to_be_processed_at: u64,
@@ -168,6 +244,7 @@ impl MessageContainer {
 
             tick_sent: 0,
             tick_received: 0,
+            tick_target: None,
             tick_to_be_processed_at: 0,
         }
     }
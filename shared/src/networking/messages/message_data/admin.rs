@@ -0,0 +1,82 @@
+use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::networking::lobby_management::LobbyState;
+
+/// A command sent by an admin client to operate the server at runtime
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", tag = "command")]
+pub enum AdminCommand {
+    /// Answered with a [`LobbyListData`]
+    ListLobbies,
+    /// Stops ticking the lobby until it gets resumed. Commands sent to the lobby in the meantime get rejected
+    PauseLobby {
+        #[serde(rename = "lobbyName")]
+        lobby_name: String,
+    },
+    ResumeLobby {
+        #[serde(rename = "lobbyName")]
+        lobby_name: String,
+    },
+    /// Ends the game of the lobby right away.
+    /// If a score is given, it replaces the current score and decides the winner
+    EndLobby {
+        #[serde(rename = "lobbyName")]
+        lobby_name: String,
+        #[schemars(with = "Option<std::collections::HashMap<String, u32>>")]
+        score: Option<HashMap<String, u32>>,
+    },
+    /// Disconnects the client from the server
+    KickClient {
+        #[serde(rename = "clientId")]
+        #[schemars(with = "u64")]
+        client_id: Entity,
+    },
+    /// How many ticks per second the lobby processes. Doesn't change how far things move per tick
+    SetTickRate {
+        #[serde(rename = "lobbyName")]
+        lobby_name: String,
+        #[serde(rename = "tickRate")]
+        tick_rate: u64,
+    },
+    /// Sends a SimpleTextMessage to everyone in the lobby, or to everyone in every lobby if no lobby is given
+    Broadcast {
+        #[serde(rename = "lobbyName")]
+        lobby_name: Option<String>,
+        message: String,
+    },
+}
+
+/// All lobbies of the server, sent as an answer to [`AdminCommand::ListLobbies`]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyListData {
+    pub lobbies: Vec<LobbyInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyInfo {
+    pub lobby_name: String,
+    pub map_name: String,
+    pub state: LobbyState,
+    pub tick: u64,
+    pub max_ticks: Option<u64>,
+    pub tick_rate: f32,
+    #[schemars(with = "std::collections::HashMap<String, u32>")]
+    pub score: HashMap<String, u32>,
+    pub players: Vec<LobbyClientInfo>,
+    #[schemars(with = "Vec<u64>")]
+    pub spectators: Vec<Entity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyClientInfo {
+    #[schemars(with = "u64")]
+    pub client_id: Entity,
+    pub client_name: String,
+    /// Dummies fill up empty slots and aren't connected clients
+    pub is_dummy: bool,
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::networking::messages::message_container::NetworkMessageType;
+
+/// What happened to the commands a player sent, sent to every player after each tick (right after the game state)
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandResultsData {
+    /// The tick the commands got processed in
+    pub tick: u64,
+    pub processed: Vec<ProcessedCommand>,
+    pub rejected: Vec<RejectedCommand>,
+}
+
+/// A command that got executed, with the values that were actually used after clamping them to the tank's limits
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(tag = "command", rename_all_fields = "camelCase")]
+pub enum ProcessedCommand {
+    MoveTankCommand {
+        requested_distance: f32,
+        effective_distance: f32,
+    },
+    RotateTankBodyCommand {
+        requested_angle: f32,
+        effective_angle: f32,
+    },
+    RotateTankTurretCommand {
+        requested_yaw_angle: f32,
+        requested_pitch_angle: f32,
+        effective_yaw_angle: f32,
+        effective_pitch_angle: f32,
+    },
+    ShootCommand,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedCommand {
+    /// The message that got rejected.
+    /// Not reflected, because NetworkMessageType contains these results itself
+    #[reflect(ignore)]
+    pub command: NetworkMessageType,
+    pub reason: CommandRejectionReason,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CommandRejectionReason {
+    /// The command arrived for a tick that isn't processed anymore
+    StaleTick,
+    /// The command targets a tick too far in the future
+    TooFarAhead,
+    /// The player is in the wrong state for this command, e.g. dead
+    WrongPlayerState,
+    /// The tank can't shoot yet
+    OnCooldown,
+    /// The client sent too many messages, see the traffic limits of the server
+    RateLimited,
+    /// Anything else, see the message
+    Invalid,
+}
+
+impl CommandResultsData {
+    pub fn reject(
+        &mut self,
+        command: NetworkMessageType,
+        reason: CommandRejectionReason,
+        message: String,
+    ) {
+        self.rejected.push(RejectedCommand {
+            command,
+            reason,
+            message,
+        });
+    }
+}
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EndTurnData {
+    /// The tick of the last game state the player received. Older turns get ignored
+    pub tick: u64,
+}
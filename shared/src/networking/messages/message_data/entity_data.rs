@@ -1,9 +1,11 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Deref, DerefMut)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Deref, DerefMut)]
 #[serde(rename_all = "camelCase")]
 pub struct EntityDataWrapper {
+    #[schemars(with = "u64")]
     pub entity_id: Entity,
 }
 
@@ -1,22 +1,32 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::game::tank_types::TankType;
+use crate::{game::tank_types::TankType, networking::lobby_management::GameMode};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct FirstContactData {
     pub bot_name: String,
-    pub lobby_name: String,
+    /// The lobby to join or create. Players that leave this empty get queued for matchmaking
+    pub lobby_name: Option<String>,
     pub map_name: Option<String>,
+    /// The mode of the lobby, only used if it gets created. Defaults to teams
+    pub game_mode: Option<GameMode>,
     pub client_type: ClientType,
 
+    /// Ignored in free-for-all lobbies, there every player gets the next free team
     pub team_name: Option<String>,
     pub bot_assigned_spawn_point: Option<usize>,
     pub tank_type: Option<TankType>,
+    /// The token of the bot from the server's bot registry. Only checked if the server has a registry.
+    /// Admin clients send the admin secret of the server instead
+    pub auth_token: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Component, Default)]
+#[derive(
+    Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Component, Default,
+)]
 #[reflect(Component)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ClientType {
@@ -25,4 +35,8 @@ pub enum ClientType {
     Player,
     #[serde(skip)]
     Dummy,
+    /// Doesn't join a lobby, but can send admin commands to the server
+    Admin,
+    /// Doesn't join a lobby, only stays connected to query the server, e.g. for a lobby browser
+    Observer,
 }
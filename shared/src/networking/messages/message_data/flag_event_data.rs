@@ -1,10 +1,13 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FlagEventDataWrapper {
+    #[schemars(with = "u64")]
     pub flag_id: Entity,
+    #[schemars(with = "u64")]
     pub carrier_id: Entity,
 }
 
@@ -17,8 +20,9 @@ impl FlagEventDataWrapper {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Deref, DerefMut)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Deref, DerefMut)]
 #[serde(rename_all = "camelCase")]
 pub struct FlagSimpleEventDataWrapper {
+    #[schemars(with = "u64")]
     pub flag_id: Entity,
 }
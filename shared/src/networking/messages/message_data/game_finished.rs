@@ -0,0 +1,36 @@
+use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Sent to everyone in the lobby when the game is over
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameFinishedData {
+    pub lobby_name: String,
+    pub map_name: String,
+    /// The tick the game ended on
+    pub tick: u64,
+    /// The final scores of the teams
+    #[schemars(with = "std::collections::HashMap<String, u32>")]
+    pub score: HashMap<String, u32>,
+    /// The team that won the game, None if it was a draw
+    pub winner: Option<String>,
+    /// The names of all players (including dummies) in each team
+    #[schemars(with = "std::collections::HashMap<String, Vec<String>>")]
+    pub teams: HashMap<String, Vec<String>>,
+}
+
+impl GameFinishedData {
+    /// Returns the team with the strictly highest score, or None if the highest score is shared
+    pub fn determine_winner(score: &HashMap<String, u32>) -> Option<String> {
+        let highest_score = score.values().max()?;
+        let mut best_teams = score
+            .iter()
+            .filter(|(_, team_score)| *team_score == highest_score);
+
+        match (best_teams.next(), best_teams.next()) {
+            (Some((team, _)), None) => Some(team.clone()),
+            _ => None,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -6,27 +7,34 @@ use crate::{
         config::TankConfig,
         maps::{MapDefinition, TeamConfig},
     },
-    game::tank_types::TankType,
+    game::{flag::FlagRules, tank_types::TankType},
 };
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Resource)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Resource)]
 #[reflect(Resource)]
 #[serde(rename_all = "camelCase")]
 pub struct GameStarts {
     pub tick_rate: u64,
+    #[schemars(with = "u64")]
     pub client_id: Entity,
     pub connected_clients: Vec<ConnectedClientConfig>,
+    #[schemars(with = "std::collections::HashMap<String, TeamConfig>")]
     pub team_configs: HashMap<String, TeamConfig>,
+    #[schemars(with = "std::collections::HashMap<TankType, TankConfig>")]
     pub tank_configs: HashMap<TankType, TankConfig>,
     pub map_definition: MapDefinition,
+    pub flag_rules: FlagRules,
 }
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectedClientConfig {
+    #[schemars(with = "u64")]
     pub client_id: Entity,
     pub client_name: String,
     pub client_team: String,
     pub client_tank_type: TankType,
     pub assigned_spawn_point: usize,
+    /// The rating of the bot, None for dummies or if the server doesn't keep ratings
+    pub client_rating: Option<f32>,
 }
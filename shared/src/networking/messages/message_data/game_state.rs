@@ -1,34 +1,49 @@
 use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::game::game_state::{ClientState, FlagBaseState, FlagGameState, ProjectileState};
+use crate::game::game_state::{
+    ClientState, FlagBaseState, FlagGameState, ObstacleState, ProjectileState,
+};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GameState {
     pub tick: u64,
     /// The score of each team.
+    #[schemars(with = "std::collections::HashMap<String, u32>")]
     pub score: HashMap<String, u32>,
     #[serde(
         serialize_with = "serialize_hashmap",
         deserialize_with = "deserialize_hashmap"
     )]
+    #[schemars(with = "std::collections::HashMap<String, Option<ClientState>>")]
     pub client_states: HashMap<Entity, Option<ClientState>>,
     #[serde(
         serialize_with = "serialize_hashmap",
         deserialize_with = "deserialize_hashmap"
     )]
+    #[schemars(with = "std::collections::HashMap<String, ProjectileState>")]
     pub projectile_states: HashMap<Entity, ProjectileState>,
     #[serde(
         serialize_with = "serialize_hashmap",
         deserialize_with = "deserialize_hashmap"
     )]
+    #[schemars(with = "std::collections::HashMap<String, FlagGameState>")]
     pub flag_states: HashMap<Entity, FlagGameState>,
     #[serde(
         serialize_with = "serialize_hashmap",
         deserialize_with = "deserialize_hashmap"
     )]
+    #[schemars(with = "std::collections::HashMap<String, FlagBaseState>")]
     pub flag_base_states: HashMap<Entity, FlagBaseState>,
+    /// The obstacles that are still standing, destroyed ones are removed
+    #[serde(
+        serialize_with = "serialize_hashmap",
+        deserialize_with = "deserialize_hashmap"
+    )]
+    #[schemars(with = "std::collections::HashMap<String, ObstacleState>")]
+    pub obstacle_states: HashMap<Entity, ObstacleState>,
 }
 
 fn serialize_hashmap<S, V>(map: &HashMap<Entity, V>, serializer: S) -> Result<S::Ok, S::Error>
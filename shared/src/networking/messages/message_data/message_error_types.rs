@@ -1,7 +1,8 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(
     rename_all = "SCREAMING_SNAKE_CASE",
     tag = "error_type",
@@ -16,4 +17,24 @@ pub enum ErrorMessageTypes {
     TeamFull(String),
     InvalidFirstContact(String),
     InvalidSenderState(String),
+    TournamentError(String),
+    RatingError(String),
+    AuthenticationFailed(String),
+    AdminError(String),
+    LobbyPaused(String),
+    TrafficLimitExceeded(String),
+    MalformedMessage(MalformedMessageData),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MalformedMessageData {
+    /// Where in the received JSON parsing failed, both starting at 1
+    pub line: usize,
+    pub column: usize,
+    /// Which part of the batch couldn't be parsed, e.g. "[2].message"
+    /// Empty if the batch itself isn't a JSON array
+    pub path: String,
+    /// The error of the JSON parser
+    pub error: String,
 }
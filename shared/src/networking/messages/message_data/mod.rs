@@ -1,15 +1,24 @@
 use bevy::prelude::*;
 
+pub mod admin;
+pub mod command_results;
+pub mod end_turn;
 pub mod entity_data;
 pub mod first_contact;
 pub mod flag_event_data;
+pub mod game_finished;
 pub mod game_starts;
 pub mod game_state;
 pub mod message_error_types;
+pub mod obstacle_destroyed;
+pub mod ping;
+pub mod rating;
+pub mod server_info;
 pub mod start_game_config;
 pub mod tank_messages;
 pub mod team_scored;
 pub mod text_data;
+pub mod tournament;
 
 pub struct MySharedMessageDataPlugin;
 
@@ -18,6 +27,7 @@ impl Plugin for MySharedMessageDataPlugin {
         app.register_type::<first_contact::FirstContactData>()
             .register_type::<first_contact::ClientType>()
             .register_type::<message_error_types::ErrorMessageTypes>()
+            .register_type::<message_error_types::MalformedMessageData>()
             .register_type::<game_starts::GameStarts>()
             .register_type::<game_starts::ConnectedClientConfig>()
             .register_type::<text_data::TextDataWrapper>()
@@ -27,6 +37,32 @@ impl Plugin for MySharedMessageDataPlugin {
             .register_type::<flag_event_data::FlagSimpleEventDataWrapper>()
             .register_type::<entity_data::EntityDataWrapper>()
             .register_type::<team_scored::TeamScoredData>()
+            .register_type::<obstacle_destroyed::ObstacleDestroyedData>()
+            .register_type::<game_finished::GameFinishedData>()
+            .register_type::<tournament::TournamentMode>()
+            .register_type::<tournament::TournamentStandingsRequest>()
+            .register_type::<tournament::TournamentStandingsData>()
+            .register_type::<tournament::TournamentStanding>()
+            .register_type::<tournament::TournamentMatchInfo>()
+            .register_type::<tournament::TournamentMatchState>()
+            .register_type::<rating::RatingsRequest>()
+            .register_type::<rating::RatingsData>()
+            .register_type::<rating::BotRating>()
+            .register_type::<admin::AdminCommand>()
+            .register_type::<admin::LobbyListData>()
+            .register_type::<admin::LobbyInfo>()
+            .register_type::<admin::LobbyClientInfo>()
+            .register_type::<end_turn::EndTurnData>()
+            .register_type::<command_results::CommandResultsData>()
+            .register_type::<command_results::ProcessedCommand>()
+            .register_type::<command_results::RejectedCommand>()
+            .register_type::<command_results::CommandRejectionReason>()
+            .register_type::<ping::PingData>()
+            .register_type::<server_info::ServerInfoRequest>()
+            .register_type::<server_info::ServerInfoData>()
+            .register_type::<server_info::MapInfo>()
+            .register_type::<server_info::LobbySummary>()
+            .register_type::<server_info::TeamSlots>()
             .add_plugins((tank_messages::MyTankMessagesPlugin,));
     }
 }
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::asset_handling::maps::{ObstacleType, TileDefinition};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObstacleDestroyedData {
+    #[schemars(with = "u64")]
+    pub obstacle_id: Entity,
+    pub obstacle_type: ObstacleType,
+    /// Tanks can drive onto this tile from now on
+    pub tile: TileDefinition,
+}
@@ -0,0 +1,10 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PingData {
+    /// The answering Pong has to contain the same id
+    pub id: u64,
+}
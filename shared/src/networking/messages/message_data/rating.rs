@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Sent by a client to the server to get the ratings of bots
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingsRequest {
+    /// Only return the ratings of these bots. If None, all ratings are returned
+    pub bot_names: Option<Vec<String>>,
+}
+
+/// The answer to a [`RatingsRequest`]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingsData {
+    /// Sorted by rating, best bot first
+    pub ratings: Vec<BotRating>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BotRating {
+    pub bot_name: String,
+    pub rating: f32,
+    pub games_played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl BotRating {
+    pub fn new(bot_name: String, rating: f32) -> Self {
+        BotRating {
+            bot_name,
+            rating,
+            ..default()
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::networking::lobby_management::{GameMode, LobbyState};
+
+/// Sent by a client to the server to find out which maps and lobbies there are
+/// Can be sent before the first contact
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfoRequest {}
+
+/// The answer to a [`ServerInfoRequest`], also sent to observers after their first contact
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInfoData {
+    /// The maps a new lobby can be created on, sorted by name
+    pub maps: Vec<MapInfo>,
+    /// Sorted by name
+    pub lobbies: Vec<LobbySummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MapInfo {
+    pub map_name: String,
+    pub width: usize,
+    pub depth: usize,
+    /// Sorted by name
+    pub teams: Vec<TeamSlots>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbySummary {
+    pub lobby_name: String,
+    pub map_name: String,
+    pub game_mode: GameMode,
+    pub state: LobbyState,
+    /// False if the lobby already started, players and spectators can't join it anymore
+    pub joinable: bool,
+    pub tick: u64,
+    pub spectators: usize,
+    /// Sorted by name
+    pub teams: Vec<TeamSlots>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSlots {
+    pub team_name: String,
+    pub max_players: usize,
+    /// How many players can still join the team. Always max players for maps
+    pub free_slots: usize,
+}
@@ -1,8 +1,24 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+use crate::game::flag::FlagRules;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct StartGameConfig {
     pub fill_empty_slots_with_dummies: bool,
+    /// The game ends after this many ticks. If None, the game runs until it gets stopped
+    pub max_ticks: Option<u64>,
+    /// If set, the lobby runs in lockstep: the next tick starts as soon as every player sent an EndTurn
+    /// for the current tick, or after this many milliseconds. If None, the lobby ticks at the tick rate
+    pub lockstep_timeout: Option<u64>,
+    /// If true, the lobby doesn't wait for real time: the next tick starts as soon as every player ended its turn,
+    /// several ticks per frame if the players keep up. Lobbies with only dummies run as fast as the server can go.
+    /// Can be combined with a lockstep timeout. The simulation is the same as in real time
+    #[serde(default)]
+    pub fast_forward: bool,
+    /// The capture the flag rules of the game, the defaults for everything left out
+    #[serde(default)]
+    pub flag_rules: FlagRules,
 }
@@ -1,14 +1,17 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::game::collision_handling::structs::Side;
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct HitMessageData {
     /// The entity that was hit
+    #[schemars(with = "u64")]
     pub hit_entity: Entity,
     /// The projectile entity that hit the entity
+    #[schemars(with = "u64")]
     pub projectile_entity: Entity,
     /// The side of the entity that was hit
     pub hit_side: Side,
@@ -16,12 +19,14 @@ pub struct HitMessageData {
     pub damage_dealt: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct GotHitMessageData {
     /// The entity that shot the entity
+    #[schemars(with = "u64")]
     pub shooter_entity: Entity,
     /// The projectile entity that hit the entity
+    #[schemars(with = "u64")]
     pub projectile_entity: Entity,
     /// The side of the entity that was hit
     pub hit_side: Side,
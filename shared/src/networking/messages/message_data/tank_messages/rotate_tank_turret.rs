@@ -1,11 +1,12 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// A command to rotate the tank's turret in a certain direction
 /// The tank's turret can be rotated in the given direction by the given angle
 /// The angle is not allowed to be higher than the tank's maximum rotation speed, but it can be lower
 /// The speed/angle is the angle rotated in one tick
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RotateTankTurretCommand {
     pub yaw_angle: f32,
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Reflect, Clone, PartialEq, Deref, DerefMut)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Deref, DerefMut)]
 #[serde(rename_all = "camelCase")]
 pub struct TextDataWrapper {
     pub message: String,
@@ -11,4 +12,4 @@ impl TextDataWrapper {
     pub fn new(message: String) -> Self {
         Self { message }
     }
-}
\ No newline at end of file
+}
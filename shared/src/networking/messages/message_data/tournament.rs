@@ -0,0 +1,95 @@
+use bevy::{prelude::*, utils::HashMap};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TournamentMode {
+    /// Every bot plays against every other bot once on each map
+    #[default]
+    RoundRobin,
+    /// The loser of each match is out, the winners play each other in the next round
+    SingleElimination,
+}
+
+/// Sent by a client to the server to get the current standings of the tournament
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentStandingsRequest {}
+
+/// The current state of the tournament, sent as an answer to a [`TournamentStandingsRequest`]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentStandingsData {
+    pub mode: TournamentMode,
+    /// Sorted by points, best bot first
+    pub standings: Vec<TournamentStanding>,
+    pub matches: Vec<TournamentMatchInfo>,
+    /// The name of the winner, once the tournament is over
+    pub winner: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentStanding {
+    pub bot_name: String,
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// 3 points for a win, 1 for a draw
+    pub points: u32,
+    pub score_for: u32,
+    pub score_against: u32,
+    /// Only used in single elimination
+    pub eliminated: bool,
+}
+
+impl TournamentStanding {
+    pub fn new(bot_name: String) -> Self {
+        TournamentStanding {
+            bot_name,
+            ..default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TournamentMatchInfo {
+    pub match_id: usize,
+    pub round: usize,
+    pub lobby_name: String,
+    pub map_name: String,
+    /// Team name -> bot name
+    #[schemars(with = "std::collections::HashMap<String, String>")]
+    pub participants: HashMap<String, String>,
+    pub state: TournamentMatchState,
+    /// Team name -> score, filled once the match is finished
+    #[schemars(with = "std::collections::HashMap<String, u32>")]
+    pub score: HashMap<String, u32>,
+    /// The name of the bot that won, None if it was a draw or the match isn't finished yet
+    pub winner: Option<String>,
+}
+
+impl TournamentMatchInfo {
+    pub fn has_participant(&self, bot_name: &str) -> bool {
+        self.participants.values().any(|bot| bot == bot_name)
+    }
+
+    pub fn team_of_participant(&self, bot_name: &str) -> Option<&String> {
+        self.participants
+            .iter()
+            .find_map(|(team, bot)| (bot == bot_name).then_some(team))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Reflect, Clone, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TournamentMatchState {
+    /// Waiting for all participants to join the lobby
+    #[default]
+    Scheduled,
+    InProgress,
+    Finished,
+}
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 pub mod message_container;
 pub mod message_data;
 pub mod message_queue;
+pub mod protocol_schema;
 
 pub struct MySharedNetworkMessagesPlugin;
 
@@ -0,0 +1,45 @@
+use schemars::{schema_for, JsonSchema};
+use serde::Serialize;
+
+use super::message_container::{MessageContainer, NetworkMessageType};
+
+/// How a Transform looks on the wire
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+pub struct TransformSchema {
+    /// x, y, z
+    translation: [f32; 3],
+    /// A quaternion, x, y, z, w
+    rotation: [f32; 4],
+    /// x, y, z
+    scale: [f32; 3],
+}
+
+/// The constraints of a message type, that can't be expressed in the JSON Schema
+/// Generated by the auto_trigger_message_received macro
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageTypeDescription {
+    /// The value of "message_type"
+    pub message_type: &'static str,
+    /// The targets a client can send this message to. Empty if only the server sends it
+    pub allowed_targets: Vec<&'static str>,
+    /// The states the sending player has to be in. Empty if it doesn't matter
+    pub required_player_states: Vec<&'static str>,
+    /// The server forwards the message to its targets instead of handling it
+    pub forwarded: bool,
+    /// Duplicates of this message in a batch are dropped if they target the same tick
+    pub unique: bool,
+}
+
+/// JSON Schema of a batch of messages, which is what gets sent in both directions
+/// The constraints of each message type are added as "x-messageTypes"
+pub fn protocol_schema() -> serde_json::Value {
+    let mut schema = serde_json::to_value(schema_for!(Vec<MessageContainer>))
+        .expect("Failed to serialize protocol schema");
+    schema["x-messageTypes"] =
+        serde_json::to_value(NetworkMessageType::message_type_descriptions())
+            .expect("Failed to serialize message type descriptions");
+
+    schema
+}
@@ -5,11 +5,12 @@ use crate::{game_state::MyGameState, networking::MyNetworkStream};
 
 pub mod despawn_delayed;
 pub mod entity_mapping;
+pub mod flag_handling;
 pub mod game_starts;
+pub mod obstacle_handling;
 pub mod player_handling;
 pub mod projectile_handling;
 pub mod smooth_transform_handling;
-pub mod flag_handling;
 
 pub struct MyGameHandlingPlugin;
 
@@ -33,6 +34,7 @@ fn add_observers(trigger: Trigger<OnAdd, MyNetworkStream>, mut commands: Command
         .observe(player_handling::update_player_state_on_game_state_update)
         .observe(projectile_handling::handle_projectile_on_game_state_update)
         .observe(flag_handling::update_flag_state_on_game_state_update)
+        .observe(obstacle_handling::update_obstacles_on_game_state_update)
         .observe(despawn_delayed::despawn_delayed_entites);
 }
 
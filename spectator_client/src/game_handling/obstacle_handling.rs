@@ -0,0 +1,76 @@
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+use shared::{
+    asset_handling::maps::ObstacleType, game::obstacle::ObstacleMarker,
+    networking::messages::message_container::GameStateTrigger,
+};
+
+use super::{entity_mapping::MyEntityMapping, DelayedDespawn};
+
+pub fn update_obstacles_on_game_state_update(
+    trigger: Trigger<GameStateTrigger>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut entity_mapping: ResMut<MyEntityMapping>,
+    existing_obstacles: Query<Entity, With<ObstacleMarker>>,
+) {
+    let game_state = &(**trigger.event());
+    let current_tick = game_state.tick;
+    let mut server_obstacle_ids = EntityHashSet::default();
+
+    for (server_side_obstacle_entity, obstacle_state) in game_state.obstacle_states.iter() {
+        server_obstacle_ids.insert(*server_side_obstacle_entity);
+
+        let client_side_obstacle_entity = entity_mapping.map_entity(*server_side_obstacle_entity);
+        // Obstacles never move, so they only have to be spawned once
+        if existing_obstacles.get(client_side_obstacle_entity).is_ok() {
+            continue;
+        }
+
+        let size = obstacle_state.collider_size;
+        let new_client_side_obstacle_entity = commands
+            .spawn((
+                Name::new(format!("Obstacle_{:?}", obstacle_state.obstacle_type)),
+                Mesh3d(meshes.add(Cuboid::new(size.x, size.y, size.z))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: obstacle_color(&obstacle_state.obstacle_type),
+                    ..default()
+                })),
+                Transform::from_translation(
+                    obstacle_state.transform.translation + Vec3::new(0.0, size.y / 2.0, 0.0),
+                ),
+                ObstacleMarker {
+                    obstacle_type: obstacle_state.obstacle_type,
+                    tile: obstacle_state.tile.clone(),
+                },
+            ))
+            .id();
+        entity_mapping.mapping.insert(
+            *server_side_obstacle_entity,
+            new_client_side_obstacle_entity,
+        );
+    }
+
+    // Destroyed obstacles are no longer part of the game state
+    entity_mapping
+        .mapping
+        .retain(|server_side_obstacle_entity, client_side_obstacle_entity| {
+            if existing_obstacles.get(*client_side_obstacle_entity).is_ok()
+                && !server_obstacle_ids.contains(server_side_obstacle_entity)
+            {
+                commands
+                    .entity(*client_side_obstacle_entity)
+                    .insert(DelayedDespawn(current_tick + 1));
+                return false;
+            }
+            true
+        });
+}
+
+fn obstacle_color(obstacle_type: &ObstacleType) -> Color {
+    match obstacle_type {
+        ObstacleType::Crate => Color::srgb(0.55, 0.4, 0.2),
+        ObstacleType::House => Color::srgb(0.7, 0.6, 0.5),
+        ObstacleType::Wall => Color::srgb(0.5, 0.45, 0.45),
+    }
+}
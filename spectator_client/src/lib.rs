@@ -3,6 +3,7 @@ use bevy_flycam::PlayerPlugin;
 use bevy_mod_billboard::plugin::BillboardPlugin;
 use game_handling::{entity_mapping::MyEntityMapping, MyGameHandlingPlugin};
 use game_state::MyGameState;
+use map_editor::MyMapEditorPlugin;
 use map_visualization::MyMapVisualizationPlugin;
 use networking::MyNetworkingPlugin;
 use shared::{networking::messages::message_data::game_starts::GameStarts, MySharedPlugin};
@@ -10,6 +11,7 @@ use ui::MyUiPlugin;
 
 pub mod game_handling;
 pub mod game_state;
+pub mod map_editor;
 pub mod map_visualization;
 pub mod networking;
 pub mod ui;
@@ -36,6 +38,7 @@ impl Plugin for MySpectatorClientPlugin {
             BillboardPlugin,
             MySharedPlugin,
             MyMapVisualizationPlugin,
+            MyMapEditorPlugin,
             MyNetworkingPlugin,
             MyUiPlugin,
             MyGameHandlingPlugin,
@@ -0,0 +1,57 @@
+use std::fs;
+
+use bevy::{prelude::*, utils::HashMap};
+use shared::asset_handling::{
+    config::ClientConfigSystemParam,
+    maps::{MapConfig, MapDefinition, TeamConfig},
+};
+
+use super::map_file_path;
+
+const NEW_MAP_SIZE: usize = 16;
+
+/// Writes a flat map with two empty teams, if the map to edit doesn't exist yet
+pub fn create_missing_map(client_config: ClientConfigSystemParam) {
+    let Some(map_name) = &client_config.client_config().preview_map else {
+        return;
+    };
+    let path = map_file_path(map_name);
+    if path.exists() {
+        return;
+    }
+
+    let teams = [("Red", (1.0, 0.0, 0.0)), ("Blue", (0.0, 0.0, 1.0))]
+        .into_iter()
+        .map(|(team_name, color)| {
+            (
+                team_name.to_string(),
+                TeamConfig {
+                    team_name: team_name.to_string(),
+                    color: color.into(),
+                    max_players: 1,
+                    players: Vec::new(),
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let map_config = MapConfig {
+        teams,
+        map: MapDefinition {
+            width: NEW_MAP_SIZE,
+            depth: NEW_MAP_SIZE,
+            floor_color: (0.5, 0.5, 0.5).into(),
+            tiles: vec![vec![0.0; NEW_MAP_SIZE]; NEW_MAP_SIZE],
+            layers: Vec::new(),
+            markers: Vec::new(),
+        },
+    };
+
+    match map_config
+        .to_ron_string()
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()))
+    {
+        Ok(()) => info!("Created the new map {}", path.display()),
+        Err(e) => error!("Failed to create map {}: {}", path.display(), e),
+    }
+}
@@ -0,0 +1,114 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    color::palettes::css::{ORANGE, WHITE},
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+use shared::asset_handling::maps::{MapConfig, MapDefinition, MarkerType, TileDefinition};
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::{MapEditor, MyEditorGizmos};
+
+/// How far along the ray from the camera the ground is searched for
+const RAY_STEP: f32 = 0.05;
+
+/// The tile under the mouse, or under the center of the screen while the camera is flying
+#[derive(Debug, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct EditorCursor(pub Option<TileDefinition>);
+
+pub fn update_editor_cursor(
+    mut cursor: ResMut<EditorCursor>,
+    preview: Res<MapPreview>,
+    map_configs: Res<Assets<MapConfig>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    let tile = map_configs.get(&preview.handle).and_then(|map_config| {
+        let window = window.get_single().ok()?;
+        let (camera, camera_transform) = camera.get_single().ok()?;
+
+        let viewport_position = match window.cursor_options.grab_mode {
+            CursorGrabMode::Locked | CursorGrabMode::Confined => window.size() / 2.0,
+            CursorGrabMode::None => window.cursor_position()?,
+        };
+        let ray = camera
+            .viewport_to_world(camera_transform, viewport_position)
+            .ok()?;
+
+        first_tile_hit(&map_config.map, ray)
+    });
+
+    if cursor.0 != tile {
+        cursor.0 = tile;
+    }
+}
+
+/// Walks along the ray until it is below the ground, which is drawn with the interpolated heights
+fn first_tile_hit(map: &MapDefinition, ray: Ray3d) -> Option<TileDefinition> {
+    let max_distance = (map.width + map.depth) as f32 * 4.0 + ray.origin.y.abs();
+    let mut distance = 0.0;
+    while distance < max_distance {
+        let point = ray.get_point(distance);
+        distance += RAY_STEP;
+
+        if map
+            .sample_height(point)
+            .is_some_and(|height| point.y <= height)
+        {
+            return map.get_tile_below(point);
+        }
+    }
+
+    None
+}
+
+/// Outlines the tile under the cursor, the marker that is being moved and the direction of the spawns
+pub fn draw_editor_cursor(
+    mut my_gizmos: Gizmos<MyEditorGizmos>,
+    cursor: Res<EditorCursor>,
+    editor: Res<MapEditor>,
+    preview: Res<MapPreview>,
+    map_configs: Res<Assets<MapConfig>>,
+) {
+    let Some(map_config) = map_configs.get(&preview.handle) else {
+        return;
+    };
+    let map = &map_config.map;
+    let rotation = Quat::from_rotation_x(-FRAC_PI_2);
+
+    if let Some(position) = cursor
+        .0
+        .clone()
+        .and_then(|tile| map.get_real_world_position_of_tile(tile))
+    {
+        my_gizmos.rect(
+            Isometry3d::new(position + Vec3::Y * 0.05, rotation),
+            Vec2::ONE,
+            WHITE,
+        );
+    }
+
+    for (index, marker) in map.markers.iter().enumerate() {
+        let Some(position) = map.get_real_world_position_of_tile(marker.tile.clone()) else {
+            continue;
+        };
+        if editor.held_marker == Some(index) {
+            my_gizmos.rect(
+                Isometry3d::new(position + Vec3::Y * 0.05, rotation),
+                Vec2::splat(0.8),
+                ORANGE,
+            );
+        }
+        if let MarkerType::Spawn { look_direction, .. } = &marker.kind {
+            let start = position + Vec3::Y * 0.1;
+            my_gizmos.arrow(
+                start,
+                start + look_direction.to_quat() * Vec3::Z * 0.5,
+                WHITE,
+            );
+        }
+    }
+}
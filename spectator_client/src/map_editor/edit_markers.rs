@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use shared::asset_handling::maps::{LookDirection, MapConfig, MarkerDefinition, MarkerType};
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::{cursor::EditorCursor, EditorTool, MapEditor};
+
+/// Places a marker of the selected tool for the selected team with a left click,
+/// or picks up the marker on the tile, so the next left click moves it.
+/// Right click removes markers, R turns spawns clockwise
+pub fn edit_markers(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<EditorCursor>,
+    preview: Res<MapPreview>,
+    mut editor: ResMut<MapEditor>,
+    mut map_configs: ResMut<Assets<MapConfig>>,
+) {
+    if !matches!(editor.tool, EditorTool::Spawn | EditorTool::FlagBase) {
+        return;
+    }
+    let Some(tile) = cursor.0.clone() else {
+        return;
+    };
+    let (left, right, rotate) = (
+        mouse.just_pressed(MouseButton::Left),
+        mouse.just_pressed(MouseButton::Right),
+        keys.just_pressed(KeyCode::KeyR),
+    );
+    if !left && !right && !rotate {
+        return;
+    }
+    let Some(map_config) = map_configs.get(&preview.handle) else {
+        return;
+    };
+    let on_tile = map_config.map.markers.iter().position(|marker| {
+        marker.tile == tile && !matches!(marker.kind, MarkerType::Obstacle { .. })
+    });
+
+    // Picking up or putting back a marker doesn't change the map
+    if left {
+        match (editor.held_marker, on_tile) {
+            (Some(_), Some(_)) => {
+                editor.held_marker = None;
+                return;
+            }
+            (None, Some(index)) => {
+                editor.held_marker = Some(index);
+                return;
+            }
+            (None, None) if editor.team.is_none() => {
+                warn!("Add a team with N before placing markers");
+                return;
+            }
+            _ => {}
+        }
+    } else if on_tile.is_none() {
+        return;
+    }
+
+    let Some(map_config) = map_configs.get_mut(&preview.handle) else {
+        return;
+    };
+    let markers = &mut map_config.map.markers;
+    if left {
+        if let Some(held) = editor.held_marker.take() {
+            // The file might have been changed since the marker got picked up
+            if let Some(marker) = markers.get_mut(held) {
+                marker.tile = tile;
+            }
+        } else if let Some(team) = editor.team.clone() {
+            let kind = new_marker(editor.tool, &team, markers);
+            markers.push(MarkerDefinition {
+                tile,
+                group: team,
+                kind,
+            });
+        }
+    } else if let Some(index) = on_tile {
+        if right {
+            markers.remove(index);
+            editor.held_marker = None;
+        } else if let MarkerType::Spawn { look_direction, .. } = &mut markers[index].kind {
+            *look_direction = match look_direction {
+                LookDirection::North => LookDirection::East,
+                LookDirection::East => LookDirection::South,
+                LookDirection::South => LookDirection::West,
+                LookDirection::West => LookDirection::North,
+            };
+        }
+    }
+
+    editor.unsaved = true;
+}
+
+/// A spawn or flag base with the lowest number that the team doesn't use yet
+fn new_marker(tool: EditorTool, team: &str, markers: &[MarkerDefinition]) -> MarkerType {
+    let used_numbers = markers
+        .iter()
+        .filter(|marker| marker.group == team)
+        .filter_map(|marker| match (&marker.kind, tool) {
+            (MarkerType::Spawn { spawn_number, .. }, EditorTool::Spawn) => Some(*spawn_number),
+            (MarkerType::FlagBase { flag_number }, EditorTool::FlagBase) => Some(*flag_number),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let number = (0..)
+        .find(|number| !used_numbers.contains(number))
+        .unwrap_or_default();
+
+    match tool {
+        EditorTool::FlagBase => MarkerType::FlagBase {
+            flag_number: number,
+        },
+        _ => MarkerType::Spawn {
+            spawn_number: number,
+            look_direction: LookDirection::default(),
+        },
+    }
+}
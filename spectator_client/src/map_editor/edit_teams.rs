@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use shared::asset_handling::maps::{MapConfig, MarkerType, SimplifiedRGB, TeamConfig};
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::MapEditor;
+
+/// Picked in this order for new teams and when cycling the color of a team
+const TEAM_COLORS: [(f32, f32, f32); 8] = [
+    (1.0, 0.0, 0.0),
+    (0.0, 0.0, 1.0),
+    (0.0, 0.8, 0.0),
+    (1.0, 1.0, 0.0),
+    (1.0, 0.5, 0.0),
+    (0.6, 0.0, 1.0),
+    (0.0, 1.0, 1.0),
+    (1.0, 0.0, 1.0),
+];
+
+/// Tab selects the next team, N adds a team, Backspace removes the selected team with its markers.
+/// +/- change how many players the selected team has and C changes its color
+pub fn edit_teams(
+    keys: Res<ButtonInput<KeyCode>>,
+    preview: Res<MapPreview>,
+    mut editor: ResMut<MapEditor>,
+    mut map_configs: ResMut<Assets<MapConfig>>,
+) {
+    let Some(map_config) = map_configs.get(&preview.handle) else {
+        return;
+    };
+    let mut team_names = map_config.get_team_names();
+    team_names.sort();
+
+    // The selected team might have been removed from the file
+    if editor
+        .team
+        .as_ref()
+        .is_none_or(|team| !team_names.contains(team))
+    {
+        let first_team = team_names.first().cloned();
+        if editor.team != first_team {
+            editor.team = first_team;
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Tab) {
+        let next = editor
+            .team
+            .as_ref()
+            .and_then(|team| team_names.iter().position(|name| name == team))
+            .map(|index| (index + 1) % team_names.len());
+        editor.team = next.map(|index| team_names[index].clone());
+        return;
+    }
+
+    let add = keys.just_pressed(KeyCode::KeyN);
+    let remove = keys.just_pressed(KeyCode::Backspace);
+    let more = keys.any_just_pressed([KeyCode::Equal, KeyCode::NumpadAdd]);
+    let fewer = keys.any_just_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]);
+    let recolor = keys.just_pressed(KeyCode::KeyC);
+    if !add && !(editor.team.is_some() && (remove || more || fewer || recolor)) {
+        return;
+    }
+
+    let Some(map_config) = map_configs.get_mut(&preview.handle) else {
+        return;
+    };
+    if add {
+        let team_name = (1..)
+            .map(|number| format!("Team {}", number))
+            .find(|name| !map_config.teams.contains_key(name))
+            .unwrap_or_default();
+        map_config.teams.insert(
+            team_name.clone(),
+            TeamConfig {
+                team_name: team_name.clone(),
+                color: TEAM_COLORS[map_config.teams.len() % TEAM_COLORS.len()].into(),
+                max_players: 1,
+                players: Vec::new(),
+            },
+        );
+        info!("Added team \"{}\"", team_name);
+        editor.team = Some(team_name);
+    } else if let Some(team_name) = editor.team.clone() {
+        if remove {
+            map_config.teams.remove(&team_name);
+            map_config.map.markers.retain(|marker| {
+                marker.group != team_name || matches!(marker.kind, MarkerType::Obstacle { .. })
+            });
+            editor.team = None;
+            editor.held_marker = None;
+            info!("Removed team \"{}\" and its markers", team_name);
+        } else if let Some(team) = map_config.teams.get_mut(&team_name) {
+            if more {
+                team.max_players += 1;
+            } else if fewer {
+                team.max_players = team.max_players.saturating_sub(1).max(1);
+            } else if recolor {
+                team.color = next_color(&team.color);
+            }
+        }
+    }
+
+    editor.unsaved = true;
+}
+
+fn next_color(color: &SimplifiedRGB) -> SimplifiedRGB {
+    let current = TEAM_COLORS
+        .iter()
+        .position(|&(r, g, b)| color.r == r && color.g == g && color.b == b);
+    let next = current.map_or(0, |index| (index + 1) % TEAM_COLORS.len());
+
+    TEAM_COLORS[next].into()
+}
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use shared::asset_handling::maps::{LayerDefinition, MapConfig, MapDefinition, TileDefinition};
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::{cursor::EditorCursor, EditorTool, MapEditor};
+
+const HEIGHT_STEP: f32 = 0.05;
+/// Used while Ctrl is held
+const LARGE_HEIGHT_STEP: f32 = 0.25;
+
+/// Raises (left click) and lowers (right click) tiles, or paints and erases the selected layer.
+/// Layers are painted as long as the button is held
+pub fn edit_tiles(
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    cursor: Res<EditorCursor>,
+    preview: Res<MapPreview>,
+    mut editor: ResMut<MapEditor>,
+    mut map_configs: ResMut<Assets<MapConfig>>,
+) {
+    let Some(tile) = cursor.0.clone() else {
+        return;
+    };
+    let Some(map) = map_configs.get(&preview.handle).map(|config| &config.map) else {
+        return;
+    };
+
+    match editor.tool {
+        EditorTool::Height => {
+            let step = if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+                LARGE_HEIGHT_STEP
+            } else {
+                HEIGHT_STEP
+            };
+            let change = if mouse.just_pressed(MouseButton::Left) {
+                step
+            } else if mouse.just_pressed(MouseButton::Right) {
+                -step
+            } else {
+                return;
+            };
+
+            let Some(map_config) = map_configs.get_mut(&preview.handle) else {
+                return;
+            };
+            // Malformed maps might have missing or short rows, those tiles can't be edited
+            let Some(height) = map_config
+                .map
+                .tiles
+                .get_mut(tile.y)
+                .and_then(|row| row.get_mut(tile.x))
+            else {
+                return;
+            };
+            // Rounded so the heights don't drift away from the steps, the map mesh starts at 0
+            *height = (((*height + change) / HEIGHT_STEP).round() * HEIGHT_STEP).max(0.0);
+            editor.unsaved = true;
+        }
+        EditorTool::Layer => {
+            let painted = is_in_layer(map, &editor, &tile);
+            let paint = if mouse.pressed(MouseButton::Left) && !painted {
+                true
+            } else if mouse.pressed(MouseButton::Right) && painted {
+                false
+            } else {
+                return;
+            };
+
+            let Some(map_config) = map_configs.get_mut(&preview.handle) else {
+                return;
+            };
+            if paint {
+                paint_layer(&mut map_config.map, &editor, tile);
+            } else {
+                erase_layer(&mut map_config.map, &editor, &tile);
+            }
+            editor.unsaved = true;
+        }
+        EditorTool::Spawn | EditorTool::FlagBase => {}
+    }
+}
+
+fn is_in_layer(map: &MapDefinition, editor: &MapEditor, tile: &TileDefinition) -> bool {
+    map.layers
+        .iter()
+        .any(|layer| layer.kind == editor.layer_type && layer.tiles.contains(tile))
+}
+
+fn paint_layer(map: &mut MapDefinition, editor: &MapEditor, tile: TileDefinition) {
+    match map
+        .layers
+        .iter_mut()
+        .find(|layer| layer.kind == editor.layer_type)
+    {
+        Some(layer) => layer.tiles.push(tile),
+        None => map.layers.push(LayerDefinition {
+            kind: editor.layer_type.clone(),
+            // Slower layers are avoided by the pathfinding, faster ones preferred
+            cost_modifier: 1.0 / editor.layer_type.speed_modifier(),
+            tiles: vec![tile],
+        }),
+    }
+}
+
+fn erase_layer(map: &mut MapDefinition, editor: &MapEditor, tile: &TileDefinition) {
+    for layer in map.layers.iter_mut() {
+        if layer.kind == editor.layer_type {
+            layer.tiles.retain(|layer_tile| layer_tile != tile);
+        }
+    }
+    map.layers.retain(|layer| !layer.tiles.is_empty());
+}
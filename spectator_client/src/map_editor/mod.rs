@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
+use shared::{
+    asset_handling::{config::ClientConfigSystemParam, maps::LayerType},
+    main_state::MyMainState,
+};
+
+use crate::map_visualization::map_preview::{self, MapPreview};
+
+pub mod create_missing_map;
+pub mod cursor;
+pub mod edit_markers;
+pub mod edit_teams;
+pub mod edit_tiles;
+pub mod save_map;
+pub mod status_text;
+
+pub struct MyMapEditorPlugin;
+
+impl Plugin for MyMapEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MapEditor>()
+            .register_type::<EditorTool>()
+            .register_type::<cursor::EditorCursor>()
+            .register_type::<status_text::EditorStatusText>()
+            .init_resource::<cursor::EditorCursor>()
+            .init_gizmo_group::<MyEditorGizmos>()
+            .add_systems(
+                OnEnter(MyMainState::Ready),
+                (
+                    create_missing_map::create_missing_map.before(map_preview::start_map_preview),
+                    start_map_editor,
+                    status_text::spawn_status_text,
+                )
+                    .run_if(is_editing_map),
+            )
+            .add_systems(
+                Update,
+                (
+                    cursor::update_editor_cursor,
+                    (
+                        select_tool,
+                        edit_tiles::edit_tiles,
+                        edit_markers::edit_markers,
+                        edit_teams::edit_teams,
+                        save_map::save_map,
+                    ),
+                    (
+                        cursor::draw_editor_cursor,
+                        status_text::update_status_text.run_if(resource_changed::<MapEditor>),
+                    ),
+                )
+                    .chain()
+                    .run_if(resource_exists::<MapEditor>.and(resource_exists::<MapPreview>)),
+            );
+    }
+}
+
+/// Set if the previewed map can be edited
+#[derive(Debug, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MapEditor {
+    pub tool: EditorTool,
+    /// The layer painted by [`EditorTool::Layer`]
+    pub layer_type: LayerType,
+    /// The team new markers belong to and whose settings are changed
+    pub team: Option<String>,
+    /// The index of the marker that gets moved with the next click
+    pub held_marker: Option<usize>,
+    pub unsaved: bool,
+}
+
+#[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq, Default)]
+pub enum EditorTool {
+    #[default]
+    Height,
+    Layer,
+    Spawn,
+    FlagBase,
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MyEditorGizmos {}
+
+pub fn is_editing_map(client_config: ClientConfigSystemParam) -> bool {
+    let client_config = client_config.client_config();
+    client_config.edit_map && client_config.preview_map.is_some()
+}
+
+/// Where the map is loaded from, the same folder the asset server reads
+pub fn map_file_path(map_name: &str) -> PathBuf {
+    FileAssetReader::get_base_path()
+        .join("assets/maps")
+        .join(format!("{}.map.ron", map_name))
+}
+
+fn start_map_editor(mut commands: Commands) {
+    commands.insert_resource(MapEditor {
+        tool: EditorTool::default(),
+        layer_type: LayerType::default(),
+        team: None,
+        held_marker: None,
+        unsaved: false,
+    });
+}
+
+fn select_tool(keys: Res<ButtonInput<KeyCode>>, mut editor: ResMut<MapEditor>) {
+    let tool = if keys.just_pressed(KeyCode::Digit1) {
+        EditorTool::Height
+    } else if keys.just_pressed(KeyCode::Digit2) {
+        EditorTool::Layer
+    } else if keys.just_pressed(KeyCode::Digit3) {
+        EditorTool::Spawn
+    } else if keys.just_pressed(KeyCode::Digit4) {
+        EditorTool::FlagBase
+    } else {
+        if keys.just_pressed(KeyCode::KeyL) {
+            editor.layer_type = match editor.layer_type {
+                LayerType::Forest => LayerType::Water,
+                LayerType::Water => LayerType::Wall,
+                LayerType::Wall => LayerType::Mud,
+                LayerType::Mud => LayerType::Road,
+                LayerType::Road => LayerType::Forest,
+            };
+        }
+        return;
+    };
+
+    if editor.tool != tool {
+        editor.tool = tool;
+        editor.held_marker = None;
+    }
+}
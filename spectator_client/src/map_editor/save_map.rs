@@ -0,0 +1,52 @@
+use std::fs;
+
+use bevy::prelude::*;
+use shared::asset_handling::maps::MapConfig;
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::{map_file_path, MapEditor};
+
+/// Writes the map back to its file with Ctrl+S, but only if it is valid.
+/// The file watcher then reloads the preview from it
+pub fn save_map(
+    keys: Res<ButtonInput<KeyCode>>,
+    preview: Res<MapPreview>,
+    map_configs: Res<Assets<MapConfig>>,
+    mut editor: ResMut<MapEditor>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyS)
+    {
+        return;
+    }
+    let Some(map_config) = map_configs.get(&preview.handle) else {
+        return;
+    };
+
+    if let Err(errors) = map_config.validate() {
+        error!(
+            "Not saving map \"{}\", it is invalid:\n\t{}",
+            preview.map_name,
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+                .join("\n\t")
+        );
+        return;
+    }
+
+    let path = map_file_path(&preview.map_name);
+    match map_config
+        .to_ron_string()
+        .map_err(|e| e.to_string())
+        .and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()))
+    {
+        Ok(()) => {
+            info!("Saved map \"{}\" to {}", preview.map_name, path.display());
+            editor.unsaved = false;
+        }
+        Err(e) => error!("Failed to save map to {}: {}", path.display(), e),
+    }
+}
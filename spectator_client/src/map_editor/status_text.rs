@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use shared::asset_handling::maps::MapConfig;
+
+use crate::map_visualization::map_preview::MapPreview;
+
+use super::{EditorTool, MapEditor};
+
+#[derive(Debug, Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct EditorStatusText;
+
+pub fn spawn_status_text(mut commands: Commands) {
+    commands.spawn((
+        EditorStatusText,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+    ));
+}
+
+/// Shows what the mouse and keys do right now
+pub fn update_status_text(
+    editor: Res<MapEditor>,
+    preview: Res<MapPreview>,
+    map_configs: Res<Assets<MapConfig>>,
+    mut text: Single<&mut Text, With<EditorStatusText>>,
+) {
+    let team = editor
+        .team
+        .as_ref()
+        .and_then(|team_name| {
+            let team = map_configs.get(&preview.handle)?.teams.get(team_name)?;
+            Some(format!("{} with {} players", team_name, team.max_players))
+        })
+        .unwrap_or_else(|| "none".to_string());
+    let tool = match editor.tool {
+        EditorTool::Height => {
+            "height: left click raises, right click lowers, hold Ctrl for bigger steps".to_string()
+        }
+        EditorTool::Layer => format!(
+            "layer {:?}: left click paints, right click erases, L selects the next layer",
+            editor.layer_type
+        ),
+        EditorTool::Spawn | EditorTool::FlagBase => format!(
+            "{}: left click places or moves, right click removes, R turns spawns",
+            if editor.tool == EditorTool::Spawn {
+                "spawn"
+            } else {
+                "flag base"
+            }
+        ),
+    };
+
+    text.0 = format!(
+        "Editing \"{}\"{}\nTool (1 height, 2 layer, 3 spawn, 4 flag base) {}\nTeam {} (Tab next, N new, Backspace remove, +/- players, C color)\nCtrl+S saves, T shows the reachability of the next tank type",
+        preview.map_name,
+        if editor.unsaved { " (unsaved)" } else { "" },
+        tool,
+        team,
+    );
+}
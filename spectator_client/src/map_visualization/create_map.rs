@@ -4,16 +4,25 @@ use bevy::{
     render::mesh::{Indices, PrimitiveTopology},
 };
 use shared::{
-    asset_handling::maps::{MapConfig, MapConfigSystemParam},
-    networking::messages::{
-        message_container::GameStartsTrigger, message_data::game_starts::GameStarts,
-    },
+    asset_handling::maps::{LayerType, MapDefinition, WALL_HEIGHT},
+    networking::messages::message_container::GameStartsTrigger,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Reflect, Component, Default)]
 #[reflect(Component)]
 pub struct MapMeshMarker;
 
+#[derive(Debug, Clone, Eq, PartialEq, Reflect, Component, Default)]
+#[reflect(Component)]
+pub struct MapLayerMarker;
+
+/// Vertices of the ground mesh per tile and axis, so its triangles follow the interpolated heights closely
+const MESH_SUBDIVISIONS: usize = 2;
+/// Heights on the far borders are sampled this far inside the map
+const BORDER_OFFSET: f32 = 1e-3;
+/// Walls are drawn as blocks as high as they block, all other layers as a thin sheet on top of the tiles
+const LAYER_SHEET_HEIGHT: f32 = 0.02;
+
 pub fn create_map(
     trigger: Trigger<GameStartsTrigger>,
     mut commands: Commands,
@@ -22,10 +31,21 @@ pub fn create_map(
 ) {
     let game_starts = (**trigger.event()).clone();
     let map_config = &game_starts.map_definition;
-    let center_of_map = map_config.get_center_of_map();
-    let highest_point = map_config.get_highest_point();
 
-    let mesh = generate_mesh_from_grid(map_config.width, map_config.depth, &map_config.tiles);
+    spawn_map(&mut commands, &mut meshes, &mut materials, map_config);
+    spawn_light(&mut commands, map_config);
+
+    commands.insert_resource(game_starts);
+}
+
+/// Spawns the ground and the layers, everything with a [`MapMeshMarker`] or [`MapLayerMarker`]
+pub fn spawn_map(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    map_config: &MapDefinition,
+) {
+    let mesh = generate_mesh_from_map(map_config);
     let mesh_handle = meshes.add(mesh);
     let material_handle = materials.add(StandardMaterial {
         base_color: Color::from(map_config.floor_color.clone()),
@@ -39,6 +59,40 @@ pub fn create_map(
         MapMeshMarker,
     ));
 
+    for layer in map_config.layers.iter() {
+        let height = match layer.kind {
+            LayerType::Wall => WALL_HEIGHT,
+            _ => LAYER_SHEET_HEIGHT,
+        };
+        let mesh_handle = meshes.add(Cuboid::new(1.0, height, 1.0));
+        let material_handle = materials.add(StandardMaterial {
+            base_color: layer_color(&layer.kind),
+            ..default()
+        });
+
+        for tile in layer.tiles.iter() {
+            let Some(floor_height) = map_config.get_floor_height_of_tile(tile.clone()) else {
+                continue;
+            };
+            commands.spawn((
+                Name::new(format!("{:?}Layer", layer.kind)),
+                Mesh3d(mesh_handle.clone()),
+                MeshMaterial3d(material_handle.clone()),
+                Transform::from_xyz(
+                    tile.x as f32 + 0.5,
+                    floor_height + height / 2.0,
+                    tile.y as f32 + 0.5,
+                ),
+                MapLayerMarker,
+            ));
+        }
+    }
+}
+
+pub fn spawn_light(commands: &mut Commands, map_config: &MapDefinition) {
+    let center_of_map = map_config.get_center_of_map();
+    let highest_point = map_config.get_highest_point();
+
     commands.spawn((
         DirectionalLight {
             shadows_enabled: true,
@@ -60,133 +114,101 @@ pub fn create_map(
             Vec3::Y,
         ),
     ));
-
-    commands.insert_resource(game_starts);
 }
 
-// This doesnt really make sense the way we use it right now. would make sense if we have a seperate run mode where we just want to observe the map
-pub fn listen_for_map_changes(
-    mut event: EventReader<AssetEvent<MapConfig>>,
-    map_config: MapConfigSystemParam,
-    mut map_mesh: Single<&mut Mesh3d, With<MapMeshMarker>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut game_starts: ResMut<GameStarts>,
-) {
-    for event in event.read() {
-        match event {
-            AssetEvent::Modified { id } => {
-                let map_config = &map_config
-                    .get_map_config_from_asset_id(*id)
-                    .expect("Map not found")
-                    .map;
-
-                let mesh =
-                    generate_mesh_from_grid(map_config.width, map_config.depth, &map_config.tiles);
-                let mesh_handle = meshes.add(mesh);
-
-                map_mesh.0 = mesh_handle;
-
-                game_starts.map_definition = map_config.clone();
-            }
-            _ => (),
-        }
+fn layer_color(kind: &LayerType) -> Color {
+    match kind {
+        LayerType::Forest => Color::srgb(0.13, 0.4, 0.13),
+        LayerType::Water => Color::srgb(0.15, 0.35, 0.8),
+        LayerType::Wall => Color::srgb(0.35, 0.3, 0.3),
+        LayerType::Mud => Color::srgb(0.4, 0.27, 0.13),
+        LayerType::Road => Color::srgb(0.25, 0.25, 0.25),
     }
 }
 
-fn generate_mesh_from_grid(width: usize, height: usize, grid: &Vec<Vec<f32>>) -> Mesh {
-    let rows = height;
-    let cols = width;
+/// The ground follows the heights the server interpolates between the tile centers, so tanks stand on it.
+/// Its border is closed with walls down to 0
+fn generate_mesh_from_map(map: &MapDefinition) -> Mesh {
+    let step = 1.0 / MESH_SUBDIVISIONS as f32;
+    let columns = map.width * MESH_SUBDIVISIONS + 1;
+    let rows = map.depth * MESH_SUBDIVISIONS + 1;
+    // The far borders belong to the last tiles, and malformed maps are drawn flat where heights are missing
+    let height_at = |x: f32, z: f32| {
+        let x = x.clamp(0.0, (map.width as f32 - BORDER_OFFSET).max(0.0));
+        let z = z.clamp(0.0, (map.depth as f32 - BORDER_OFFSET).max(0.0));
+        map.sample_height(Vec3::new(x, 0.0, z)).unwrap_or_default()
+    };
 
     let mut positions: Vec<[f32; 3]> = Vec::new();
     let mut normals: Vec<[f32; 3]> = Vec::new();
     let mut uvs: Vec<[f32; 2]> = Vec::new();
     let mut indices: Vec<u32> = Vec::new();
 
+    // -- Ground, one vertex every step --
     for row in 0..rows {
-        for col in 0..cols {
-            let cell_top = grid[row][col];
-            let cell_bottom = 0.0;
-            let x = col as f32;
-            let z = row as f32;
-
-            // -- Top Face (normal: [0, 1, 0]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x, cell_top, z]);
-                positions.push([x + 1.0, cell_top, z]);
-                positions.push([x + 1.0, cell_top, z + 1.0]);
-                positions.push([x, cell_top, z + 1.0]);
-
-                normals.extend_from_slice(&[[0.0, 1.0, 0.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                // Reverse the winding order: [0,2,1] and [0,3,2]
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
-
-            // -- Bottom Face (normal: [0, -1, 0]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x, cell_bottom, z + 1.0]);
-                positions.push([x + 1.0, cell_bottom, z + 1.0]);
-                positions.push([x + 1.0, cell_bottom, z]);
-                positions.push([x, cell_bottom, z]);
-
-                normals.extend_from_slice(&[[0.0, -1.0, 0.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
-
-            // -- Front Face (facing -Z, normal: [0, 0, -1]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x, cell_bottom, z]); // bottom left
-                positions.push([x + 1.0, cell_bottom, z]); // bottom right
-                positions.push([x + 1.0, cell_top, z]); // top right
-                positions.push([x, cell_top, z]); // top left
-
-                normals.extend_from_slice(&[[0.0, 0.0, -1.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
-
-            // -- Back Face (facing +Z, normal: [0, 0, 1]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x + 1.0, cell_bottom, z + 1.0]); // bottom left
-                positions.push([x, cell_bottom, z + 1.0]); // bottom right
-                positions.push([x, cell_top, z + 1.0]); // top right
-                positions.push([x + 1.0, cell_top, z + 1.0]); // top left
-
-                normals.extend_from_slice(&[[0.0, 0.0, 1.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
-
-            // -- Left Face (facing -X, normal: [-1, 0, 0]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x, cell_bottom, z + 1.0]); // bottom left
-                positions.push([x, cell_bottom, z]); // bottom right
-                positions.push([x, cell_top, z]); // top right
-                positions.push([x, cell_top, z + 1.0]); // top left
-
-                normals.extend_from_slice(&[[-1.0, 0.0, 0.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
-
-            // -- Right Face (facing +X, normal: [1, 0, 0]) --
-            {
-                let base = positions.len() as u32;
-                positions.push([x + 1.0, cell_bottom, z]); // bottom left
-                positions.push([x + 1.0, cell_bottom, z + 1.0]); // bottom right
-                positions.push([x + 1.0, cell_top, z + 1.0]); // top right
-                positions.push([x + 1.0, cell_top, z]); // top left
-
-                normals.extend_from_slice(&[[1.0, 0.0, 0.0]; 4]);
-                uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
-                indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-            }
+        for column in 0..columns {
+            let (x, z) = (column as f32 * step, row as f32 * step);
+            let slope_x = height_at(x + step, z) - height_at(x - step, z);
+            let slope_z = height_at(x, z + step) - height_at(x, z - step);
+
+            positions.push([x, height_at(x, z), z]);
+            normals.push(
+                Vec3::new(-slope_x, 2.0 * step, -slope_z)
+                    .normalize()
+                    .to_array(),
+            );
+            uvs.push([x, z]);
+        }
+    }
+    for row in 0..rows.saturating_sub(1) {
+        for column in 0..columns.saturating_sub(1) {
+            let near_left = (row * columns + column) as u32;
+            let near_right = near_left + 1;
+            let far_left = near_left + columns as u32;
+            let far_right = far_left + 1;
+            indices.extend_from_slice(&[
+                near_left, far_right, near_right, near_left, far_left, far_right,
+            ]);
+        }
+    }
+
+    // -- Border walls, each running along the border so that it faces outwards --
+    let vertex = |row: usize, column: usize| Vec3::from(positions[row * columns + column]);
+    let borders = [
+        (
+            (0..columns)
+                .map(|column| vertex(0, column))
+                .collect::<Vec<_>>(),
+            Vec3::NEG_Z,
+        ),
+        (
+            (0..columns)
+                .rev()
+                .map(|column| vertex(rows - 1, column))
+                .collect(),
+            Vec3::Z,
+        ),
+        (
+            (0..rows).rev().map(|row| vertex(row, 0)).collect(),
+            Vec3::NEG_X,
+        ),
+        (
+            (0..rows).map(|row| vertex(row, columns - 1)).collect(),
+            Vec3::X,
+        ),
+    ];
+    for (border, normal) in borders {
+        for edge in border.windows(2) {
+            let (start, end) = (edge[0], edge[1]);
+            let base = positions.len() as u32;
+            positions.push(start.with_y(0.0).to_array()); // bottom left
+            positions.push(end.with_y(0.0).to_array()); // bottom right
+            positions.push(end.to_array()); // top right
+            positions.push(start.to_array()); // top left
+
+            normals.extend_from_slice(&[normal.to_array(); 4]);
+            uvs.extend_from_slice(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
+            indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
         }
     }
 
@@ -0,0 +1,249 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    color::palettes::css::{GREEN, RED, YELLOW},
+    prelude::*,
+};
+use shared::{
+    asset_handling::{
+        config::{ClientConfigSystemParam, TankConfigSystemParam},
+        maps::{MapConfig, MarkerType},
+    },
+    game::{flag::FlagRules, tank_types::TankType},
+    networking::messages::message_data::game_starts::GameStarts,
+};
+
+use super::create_map::{spawn_light, spawn_map, MapLayerMarker, MapMeshMarker};
+
+/// Set if the client only shows a map from disk instead of connecting to a server
+#[derive(Debug, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MapPreview {
+    pub map_name: String,
+    pub handle: Handle<MapConfig>,
+    /// The tank type whose reachability is drawn, cycled with T
+    pub tank_type: TankType,
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct MyReachabilityGizmos {}
+
+const REACHABILITY_SIZE: f32 = 0.15;
+/// Routes are drawn a bit above the ground, so they don't vanish in slopes
+const ROUTE_HEIGHT: f32 = 0.2;
+
+pub fn is_previewing_map(client_config: ClientConfigSystemParam) -> bool {
+    client_config.client_config().preview_map.is_some()
+}
+
+pub fn start_map_preview(
+    client_config: ClientConfigSystemParam,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(map_name) = client_config.client_config().preview_map.clone() else {
+        return;
+    };
+
+    info!("Previewing map \"{}\", it reloads on every save", map_name);
+    commands.insert_resource(MapPreview {
+        handle: asset_server.load(format!("maps/{}.map.ron", map_name)),
+        map_name,
+        tank_type: TankType::default(),
+    });
+}
+
+/// Shows the previewed map once it is loaded and again every time its file changes.
+/// Invalid maps are still shown, so they can be fixed while looking at them
+pub fn update_map_preview(
+    mut events: EventReader<AssetEvent<MapConfig>>,
+    preview: Res<MapPreview>,
+    map_configs: Res<Assets<MapConfig>>,
+    tank_configs: TankConfigSystemParam,
+    map_entities: Query<Entity, Or<(With<MapMeshMarker>, With<MapLayerMarker>)>>,
+    mut commands: Commands,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+) {
+    let changed = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == preview.handle.id()
+        }
+        _ => false,
+    });
+    // The maps are loaded before the client is ready, so there might be no event for the first load
+    let first_load = map_entities.is_empty();
+    if !changed && !first_load {
+        return;
+    }
+    let Some(map_config) = map_configs.get(&preview.handle) else {
+        return;
+    };
+
+    if let Err(errors) = map_config.validate() {
+        error!(
+            "Map \"{}\" is invalid:\n\t{}",
+            preview.map_name,
+            errors
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+                .join("\n\t")
+        );
+    }
+    for unreachable_flag in map_config.find_unreachable_flags(tank_configs.tank_configs()) {
+        warn!("Map \"{}\": {}", preview.map_name, unreachable_flag);
+    }
+
+    for entity in map_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_map(&mut commands, &mut meshes, &mut materials, &map_config.map);
+    if first_load {
+        spawn_light(&mut commands, &map_config.map);
+    }
+
+    commands.insert_resource(GameStarts {
+        tick_rate: 0,
+        client_id: Entity::PLACEHOLDER,
+        connected_clients: Vec::new(),
+        team_configs: map_config.teams.clone(),
+        tank_configs: tank_configs.tank_configs().tanks.clone(),
+        map_definition: map_config.map.clone(),
+        flag_rules: FlagRules::default(),
+    });
+}
+
+pub fn cycle_preview_tank_type(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut preview: ResMut<MapPreview>,
+    game_starts: Res<GameStarts>,
+) {
+    if !keys.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+
+    let mut tank_types = game_starts.tank_configs.keys().collect::<Vec<_>>();
+    tank_types.sort_by_key(|tank_type| format!("{:?}", tank_type));
+    let Some(current) = tank_types
+        .iter()
+        .position(|tank_type| **tank_type == preview.tank_type)
+    else {
+        return;
+    };
+
+    preview.tank_type = tank_types[(current + 1) % tank_types.len()].clone();
+    info!("Showing the reachability of {:?}", preview.tank_type);
+}
+
+/// What the previewed tank type can do on the map. Only computed again when the map or the tank type changes
+#[derive(Debug, Default, Resource, Reflect)]
+#[reflect(Resource)]
+pub struct PreviewAnalysis {
+    /// Every tile, if the tank type can drive to it from any spawn
+    pub reachable_tiles: Vec<(Vec3, bool)>,
+    /// The cheapest routes from every spawn to the flag bases of the other teams, in the color of the spawn's team
+    pub routes: Vec<(Vec<Vec3>, Color)>,
+}
+
+pub fn update_preview_analysis(
+    preview: Res<MapPreview>,
+    game_starts: Res<GameStarts>,
+    mut analysis: ResMut<PreviewAnalysis>,
+) {
+    if !preview.is_changed() && !game_starts.is_changed() {
+        return;
+    }
+
+    *analysis = match game_starts.tank_configs.get(&preview.tank_type) {
+        Some(tank_config) => PreviewAnalysis {
+            reachable_tiles: find_reachable_tiles(&game_starts, tank_config.max_slope),
+            routes: find_routes(&game_starts, tank_config.max_slope),
+        },
+        None => PreviewAnalysis::default(),
+    };
+}
+
+fn find_reachable_tiles(game_starts: &GameStarts, max_slope: f32) -> Vec<(Vec3, bool)> {
+    let map_definition = &game_starts.map_definition;
+    let mut reachable = vec![false; map_definition.width * map_definition.depth];
+    for marker in map_definition.markers.iter() {
+        if !matches!(marker.kind, MarkerType::Spawn { .. }) {
+            continue;
+        }
+        let from_spawn = map_definition.reachable_tiles(&marker.tile, max_slope);
+        for (reachable, from_spawn) in reachable.iter_mut().zip(from_spawn) {
+            *reachable |= from_spawn;
+        }
+    }
+
+    reachable
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, reachable)| {
+            let tile = (index % map_definition.width, index / map_definition.width);
+            map_definition
+                .get_real_world_position_of_tile(tile)
+                .map(|position| (position, reachable))
+        })
+        .collect()
+}
+
+fn find_routes(game_starts: &GameStarts, max_slope: f32) -> Vec<(Vec<Vec3>, Color)> {
+    let map_definition = &game_starts.map_definition;
+    let spawns = map_definition
+        .markers
+        .iter()
+        .filter(|marker| matches!(marker.kind, MarkerType::Spawn { .. }));
+
+    let mut routes = Vec::new();
+    for spawn in spawns {
+        let color = game_starts
+            .team_configs
+            .get(&spawn.group)
+            .map(|config| Color::from(config.color.clone()))
+            .unwrap_or(YELLOW.into());
+        let enemy_flag_bases = map_definition.markers.iter().filter(|marker| {
+            matches!(marker.kind, MarkerType::FlagBase { .. }) && marker.group != spawn.group
+        });
+
+        for flag_base in enemy_flag_bases {
+            let Some(path) = map_definition.find_tile_path(
+                spawn.tile.clone(),
+                flag_base.tile.clone(),
+                max_slope,
+            ) else {
+                continue;
+            };
+            routes.push((path.world_positions(map_definition), color));
+        }
+    }
+
+    routes
+}
+
+/// Marks every tile green that the previewed tank type can drive to from any spawn, all others red
+pub fn draw_reachability(
+    mut my_gizmos: Gizmos<MyReachabilityGizmos>,
+    analysis: Res<PreviewAnalysis>,
+) {
+    let rotation = Quat::from_rotation_x(-FRAC_PI_2);
+    for (position, reachable) in analysis.reachable_tiles.iter() {
+        my_gizmos.rect(
+            Isometry3d::new(*position + Vec3::Y * 0.05, rotation),
+            Vec2::splat(REACHABILITY_SIZE),
+            if *reachable { GREEN } else { RED },
+        );
+    }
+}
+
+/// Draws the cheapest route of the previewed tank type from every spawn to the flag bases of the other teams
+pub fn draw_routes(mut my_gizmos: Gizmos<MyReachabilityGizmos>, analysis: Res<PreviewAnalysis>) {
+    for (route, color) in analysis.routes.iter() {
+        my_gizmos.linestrip(
+            route
+                .iter()
+                .map(|position| *position + Vec3::Y * ROUTE_HEIGHT),
+            *color,
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use create_map::{listen_for_map_changes, MapMeshMarker};
+use create_map::{MapLayerMarker, MapMeshMarker};
+use map_preview::{MapPreview, MyReachabilityGizmos, PreviewAnalysis};
 use shared::{
     game::player_handling::TankTurretMarker, main_state::MyMainState,
     networking::messages::message_data::game_starts::GameStarts,
@@ -11,6 +12,7 @@ use visulize_turret_ranges::{draw_turret_ranges, MyTurretRangeGizmos};
 use crate::networking::MyNetworkStream;
 
 pub mod create_map;
+pub mod map_preview;
 pub mod visualize_markers;
 pub mod visualize_players;
 pub mod visualize_positions;
@@ -21,17 +23,35 @@ pub struct MyMapVisualizationPlugin;
 impl Plugin for MyMapVisualizationPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<MapMeshMarker>()
+            .register_type::<MapLayerMarker>()
+            .register_type::<MapPreview>()
+            .register_type::<PreviewAnalysis>()
+            .init_resource::<PreviewAnalysis>()
             .init_gizmo_group::<MyMarkerGizmos>()
             .init_gizmo_group::<MyPositionGizmos>()
             .init_gizmo_group::<MyTurretRangeGizmos>()
+            .init_gizmo_group::<MyReachabilityGizmos>()
+            .add_systems(
+                OnEnter(MyMainState::Ready),
+                map_preview::start_map_preview.run_if(map_preview::is_previewing_map),
+            )
             .add_systems(
                 Update,
-                ((
-                    (listen_for_map_changes,).run_if(any_with_component::<MapMeshMarker>),
-                    (draw_turret_ranges,).run_if(any_with_component::<TankTurretMarker>),
-                    (draw_markers, visualize_cells),
+                (
+                    (map_preview::update_map_preview,).run_if(resource_exists::<MapPreview>),
+                    (
+                        (draw_turret_ranges,).run_if(any_with_component::<TankTurretMarker>),
+                        (draw_markers, visualize_cells),
+                        (
+                            map_preview::cycle_preview_tank_type,
+                            map_preview::update_preview_analysis,
+                            (map_preview::draw_reachability, map_preview::draw_routes),
+                        )
+                            .chain()
+                            .run_if(resource_exists::<MapPreview>),
+                    )
+                        .run_if(resource_exists::<GameStarts>),
                 )
-                    .run_if(resource_exists::<GameStarts>),)
                     .run_if(in_state(MyMainState::Ready)),
             )
             .add_observer(add_observers_to_client);
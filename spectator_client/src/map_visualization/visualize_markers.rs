@@ -5,10 +5,16 @@ use shared::{
     asset_handling::maps::MarkerType, networking::messages::message_data::game_starts::GameStarts,
 };
 
+use super::map_preview::MapPreview;
+
 #[derive(Default, Reflect, GizmoConfigGroup)]
 pub struct MyMarkerGizmos {}
 
-pub fn draw_markers(mut my_gizmos: Gizmos<MyMarkerGizmos>, game_config: Res<GameStarts>) {
+pub fn draw_markers(
+    mut my_gizmos: Gizmos<MyMarkerGizmos>,
+    game_config: Res<GameStarts>,
+    preview: Option<Res<MapPreview>>,
+) {
     let map_definition = &game_config.map_definition;
 
     for marker in &map_definition.markers {
@@ -20,9 +26,11 @@ pub fn draw_markers(mut my_gizmos: Gizmos<MyMarkerGizmos>, game_config: Res<Game
 
         let marker_type = &marker.kind;
         let tile = &marker.tile;
-        let position = map_definition
-            .get_real_world_position_of_tile((tile.x, tile.y))
-            .expect("Failed to get real world position of tile");
+        // Previewed maps might be invalid, their errors are logged
+        let Some(position) = map_definition.get_real_world_position_of_tile((tile.x, tile.y))
+        else {
+            continue;
+        };
         let rotation = Quat::from_rotation_x(-FRAC_PI_2);
 
         match marker_type {
@@ -40,6 +48,20 @@ pub fn draw_markers(mut my_gizmos: Gizmos<MyMarkerGizmos>, game_config: Res<Game
                     color,
                 );
             }
+            // In a game they are spawned from the game state instead, as they disappear once destroyed
+            MarkerType::Obstacle { obstacle_type, .. } => {
+                if preview.is_some() {
+                    let half_size = obstacle_type.half_size();
+                    my_gizmos.primitive_3d(
+                        &Cuboid { half_size },
+                        Isometry3d::new(
+                            position + Vec3::new(0.0, half_size.y, 0.0),
+                            Quat::IDENTITY,
+                        ),
+                        color,
+                    );
+                }
+            }
         }
     }
 }
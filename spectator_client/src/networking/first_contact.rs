@@ -25,7 +25,8 @@ pub fn send_first_contact(
                 client_type: ClientType::Spectator,
                 bot_name: client_config.name.clone(),
                 map_name: Some(client_config.map.clone()),
-                lobby_name: client_config.lobby_name.clone(),
+                lobby_name: Some(client_config.lobby_name.clone()),
+                game_mode: Some(client_config.game_mode),
                 ..default()
             }),
         ));
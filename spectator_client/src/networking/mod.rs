@@ -6,20 +6,31 @@ use shared::{
     main_state::MyMainState, networking::messages::message_queue::ImmediateOutMessageQueue,
 };
 
+use crate::map_visualization::map_preview::is_previewing_map;
+
 pub mod connect;
 pub mod first_contact;
 pub mod message_handling;
+pub mod ping;
 
 pub struct MyNetworkingPlugin;
 
 impl Plugin for MyNetworkingPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((MyMessageHandlingPlugin,))
-            .add_systems(OnEnter(MyMainState::Ready), (connect::connect_to_server,))
-            .add_observer(first_contact::send_first_contact);
+            .add_systems(
+                OnEnter(MyMainState::Ready),
+                (connect::connect_to_server,).run_if(not(is_previewing_map)),
+            )
+            .add_observer(first_contact::send_first_contact)
+            .add_observer(add_observers_to_client);
     }
 }
 
+fn add_observers_to_client(trigger: Trigger<OnAdd, MyNetworkStream>, mut commands: Commands) {
+    commands.entity(trigger.entity()).observe(ping::answer_ping);
+}
+
 #[derive(Component, Debug, Deref, DerefMut)]
 #[require(ImmediateOutMessageQueue)]
 pub struct MyNetworkStream(pub TcpStream);
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+use shared::networking::messages::{
+    message_container::{MessageContainer, MessageTarget, NetworkMessageType, PingTrigger},
+    message_queue::ImmediateOutMessageQueue,
+};
+
+/// Answers the pings of the server, so it can measure the round trip time
+pub fn answer_ping(
+    trigger: Trigger<PingTrigger>,
+    mut message: Query<&mut ImmediateOutMessageQueue>,
+) {
+    if let Ok(mut message) = message.get_mut(trigger.entity()) {
+        message.push_back(MessageContainer::new(
+            MessageTarget::ServerOnly,
+            NetworkMessageType::Pong(trigger.message.clone()),
+        ));
+    }
+}
@@ -76,6 +76,10 @@ fn button_pressed(
             MessageTarget::ToLobbyDirectly,
             NetworkMessageType::StartGame(StartGameConfig {
                 fill_empty_slots_with_dummies: client_config.fill_empty_slots_with_dummies,
+                max_ticks: client_config.max_ticks,
+                lockstep_timeout: client_config.lockstep_timeout,
+                fast_forward: client_config.fast_forward,
+                flag_rules: client_config.flag_rules.clone(),
             }),
         ));
     }